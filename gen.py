@@ -9,6 +9,25 @@ def hex_to_rgb_tuple(hex_string: str):
     return tuple(int(hex_string[i + 1 : i + 3], 16) for i in (0, 2, 4))
 
 
+def cell_color(value: str):
+    if value in ("CellForeground", "CellBackground"):
+        return "CellColor::{}".format(value)
+
+    return "RGB8::new{}".format(hex_to_rgb_tuple(value))
+
+
+def print_color_pair(name: str, section, fg_key="foreground", bg_key="background"):
+    if not section or fg_key not in section or bg_key not in section:
+        print("{}: None,".format(name))
+        return
+
+    print(
+        "{}: Some(ColorPair::new({}, {})),".format(
+            name, cell_color(section[fg_key]), cell_color(section[bg_key])
+        )
+    )
+
+
 if __name__ == "__main__":
     with open("orig.yml", "r") as stream:
         parsed = yaml.safe_load(stream)
@@ -120,5 +139,37 @@ if __name__ == "__main__":
         print("},")
         # END BRIGHT
 
+        colors = parsed["colors"]
+
+        if "dim" in colors:
+            print("dim: Some(ThemeColors {")
+            for name in ("black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"):
+                print("{}: RGB8::new{},".format(name, hex_to_rgb_tuple(colors["dim"][name])))
+            print("}),")
+        else:
+            print("dim: None,")
+
+        print("indexed_colors: vec![")
+        for entry in colors.get("indexed_colors") or []:
+            print(
+                "IndexedColor {{ index: {}, color: RGB8::new{} }},".format(
+                    entry["index"], hex_to_rgb_tuple("#" + entry["color"][-6:])
+                )
+            )
+        print("],")
+
+        search = colors.get("search") or {}
+        hints = colors.get("hints") or {}
+
+        print_color_pair("cursor", colors.get("cursor"), "text", "cursor")
+        print_color_pair("vi_mode_cursor", colors.get("vi_mode_cursor"), "text", "cursor")
+        print_color_pair("selection", colors.get("selection"), "text", "background")
+        print_color_pair("search_matches", search.get("matches"))
+        print_color_pair("search_focused_match", search.get("focused_match"))
+        print_color_pair("hints_start", hints.get("start"))
+        print_color_pair("hints_end", hints.get("end"))
+        print_color_pair("footer_bar", colors.get("footer_bar"))
+        print_color_pair("line_indicator", colors.get("line_indicator"))
+
         print("},")
 
@@ -0,0 +1,211 @@
+use crate::discover;
+use crate::error::{Error, Result};
+use chrono::NaiveDateTime;
+use std::ffi::OsStr;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process;
+
+/// Format of the timestamp in backup file names. Sorts chronologically.
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S-%3f";
+/// Length of a formatted timestamp.
+const TIMESTAMP_LEN: usize = 19;
+
+/// A previous version of a config.
+#[derive(Debug)]
+pub struct Backup {
+    /// Timestamp part of the file name, used to refer to the backup.
+    pub id: String,
+    pub path: PathBuf,
+    /// Local time the config was replaced at.
+    pub taken: NaiveDateTime,
+}
+
+/// Directory that backups of previous configs are kept in, in a directory
+/// per config.
+pub fn dir() -> Result<PathBuf> {
+    let mut dir = discover::config_home()?;
+    dir.push("alacritty-conf");
+    dir.push("backups");
+    Ok(dir)
+}
+
+/// Directory that backups of the config at `path` are kept in. Every config
+/// gets its own, named after its full path, so configs that only share a
+/// file name don't share backups.
+fn target_dir(path: &Path) -> Result<PathBuf> {
+    let name = canonical(path)
+        .to_string_lossy()
+        .replace('%', "%25")
+        .replace('/', "%2F")
+        .replace('\\', "%5C")
+        .replace(':', "%3A");
+    Ok(dir()?.join(name))
+}
+
+/// `path` made absolute with symlinks resolved, as far as it exists.
+fn canonical(path: &Path) -> PathBuf {
+    if let Ok(target) = fs::canonicalize(path) {
+        return target;
+    }
+
+    // the config may have been deleted, but its directory is still there
+    let parent = match path.parent() {
+        Some(parent) if parent.as_os_str().is_empty() => Path::new("."),
+        Some(parent) => parent,
+        None => return path.to_owned(),
+    };
+    match (fs::canonicalize(parent), path.file_name()) {
+        (Ok(parent), Some(name)) => parent.join(name),
+        _ => path.to_owned(),
+    }
+}
+
+/// File remembering which backup of `path` the last undo went back to.
+pub fn undo_cursor(path: &Path) -> Result<PathBuf> {
+    Ok(target_dir(path)?.join("undo"))
+}
+
+/// Replace the config at `path` with `contents`.
+///
+/// The new config is written to a temporary file next to the old one and
+/// then renamed over it, so the config is never left half-written. If a
+/// config already exists, it's first copied to the backup directory, and
+/// only the `keep` newest backups are kept around.
+pub fn write(path: &Path, contents: &str, keep: usize) -> Result<()> {
+    // write through symlinks (e.g. into a dotfiles repo) instead of replacing
+    // the link with a regular file
+    let target = canonical(path);
+
+    if keep > 0 && target.exists() {
+        backup(&target)?;
+        prune(&target, keep)?;
+    }
+
+    let tmp_path = tmp_path(&target);
+    let result = write_tmp(&target, &tmp_path, contents)
+        .and_then(|_| fs::rename(&tmp_path, &target).map_err(|e| Error::from(e).in_file(&target)));
+
+    if result.is_err() {
+        // the original error is more useful than one about the cleanup
+        let _ = fs::remove_file(&tmp_path);
+    }
+
+    result
+}
+
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut name = OsStr::new(".").to_owned();
+    name.push(
+        path.file_name()
+            .unwrap_or_else(|| OsStr::new("alacritty.yml")),
+    );
+    name.push(format!(".tmp-{}", process::id()));
+    path.with_file_name(name)
+}
+
+fn write_tmp(path: &Path, tmp_path: &Path, contents: &str) -> Result<()> {
+    let in_tmp = |e| Error::from(e).in_file(tmp_path);
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(tmp_path)
+        .map_err(in_tmp)?;
+
+    file.write_all(contents.as_bytes()).map_err(in_tmp)?;
+    file.sync_all().map_err(in_tmp)?;
+
+    // keep the permissions of the config being replaced
+    if let Ok(metadata) = fs::metadata(path) {
+        fs::set_permissions(tmp_path, metadata.permissions()).map_err(in_tmp)?;
+    }
+
+    Ok(())
+}
+
+/// Name of a backup of `path` taken now, e.g.
+/// `alacritty-20200815-142501-042.yml`.
+fn backup_name(path: &Path) -> String {
+    let timestamp = chrono::Local::now().format(TIMESTAMP_FORMAT);
+
+    match path.extension() {
+        Some(ext) => format!("{}-{}.{}", stem(path), timestamp, ext.to_string_lossy()),
+        None => format!("{}-{}", stem(path), timestamp),
+    }
+}
+
+fn stem(path: &Path) -> String {
+    path.file_stem().map_or_else(
+        || "alacritty".to_owned(),
+        |s| s.to_string_lossy().into_owned(),
+    )
+}
+
+/// Timestamp of a backup of `path`, or `None` if `name` isn't the name of
+/// one. The extension has to match too, so that a backup is never mistaken
+/// for one of a config in another format.
+fn timestamp<'a>(name: &'a str, path: &Path) -> Option<&'a str> {
+    let rest = name.strip_prefix(&stem(path))?.strip_prefix('-')?;
+    let (timestamp, ext) = (rest.get(..TIMESTAMP_LEN)?, &rest[TIMESTAMP_LEN..]);
+
+    let same_ext = match path.extension() {
+        Some(expected) => ext.strip_prefix('.').is_some_and(|ext| expected == ext),
+        None => ext.is_empty(),
+    };
+    same_ext.then_some(timestamp)
+}
+
+/// Copy the config at `path` to its backup directory.
+fn backup(path: &Path) -> Result<PathBuf> {
+    let dir = target_dir(path)?;
+    fs::create_dir_all(&dir).map_err(|e| Error::from(e).in_file(&dir))?;
+
+    let backup_path = dir.join(backup_name(path));
+    fs::copy(path, &backup_path).map_err(|e| Error::from(e).in_file(&backup_path))?;
+
+    Ok(backup_path)
+}
+
+/// Backups of `path`, oldest first.
+pub fn list(path: &Path) -> Result<Vec<Backup>> {
+    let path = canonical(path);
+    let dir = target_dir(&path)?;
+
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(ref e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(Error::from(e).in_file(&dir)),
+    };
+
+    let mut backups: Vec<Backup> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let id = timestamp(entry.file_name().to_str()?, &path)?.to_owned();
+            let taken = NaiveDateTime::parse_from_str(&id, TIMESTAMP_FORMAT).ok()?;
+
+            Some(Backup {
+                id,
+                path: entry.path(),
+                taken,
+            })
+        })
+        .collect();
+
+    // the timestamp format sorts chronologically
+    backups.sort_by(|a, b| a.id.cmp(&b.id));
+
+    Ok(backups)
+}
+
+/// Delete all but the `keep` newest backups of `path`.
+fn prune(path: &Path, keep: usize) -> Result<()> {
+    let backups = list(path)?;
+
+    for old in backups.iter().take(backups.len().saturating_sub(keep)) {
+        fs::remove_file(&old.path).map_err(|e| Error::from(e).in_file(&old.path))?;
+    }
+
+    Ok(())
+}
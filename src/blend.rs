@@ -0,0 +1,81 @@
+use crate::color;
+use crate::theme::{CellColor, ColorPair, IndexedColor, Theme, ThemeColors};
+use rgb::RGB8;
+
+/// The theme `ratio` of the way from `a` to `b`, with every color mixed in
+/// OKLab. Where only one of the themes sets a color, or the colors can't be
+/// mixed, such as a fixed color and a reference to the cell's color, the
+/// closer theme's is used.
+pub fn blend(a: &Theme, b: &Theme, ratio: f64) -> Theme {
+    let mix = |x: RGB8, y: RGB8| color::mix(x, y, ratio);
+
+    let mix_colors = |x: &ThemeColors, y: &ThemeColors| ThemeColors {
+        black: mix(x.black, y.black),
+        red: mix(x.red, y.red),
+        green: mix(x.green, y.green),
+        yellow: mix(x.yellow, y.yellow),
+        blue: mix(x.blue, y.blue),
+        magenta: mix(x.magenta, y.magenta),
+        cyan: mix(x.cyan, y.cyan),
+        white: mix(x.white, y.white),
+    };
+    let mix_cell = |x: Option<CellColor>, y: Option<CellColor>| match (x, y) {
+        (Some(CellColor::Rgb(x)), Some(CellColor::Rgb(y))) => Some(CellColor::Rgb(mix(x, y))),
+        (x, y) => nearest(x, y, ratio),
+    };
+    let mix_pair = |x: Option<ColorPair>, y: Option<ColorPair>| match (x, y) {
+        (Some(x), Some(y)) => Some(ColorPair {
+            foreground: mix_cell(x.foreground, y.foreground),
+            background: mix_cell(x.background, y.background),
+        }),
+        (x, y) => nearest(x, y, ratio),
+    };
+
+    let dim = match (a.dim, b.dim) {
+        (Some(x), Some(y)) => Some(mix_colors(&x, &y)),
+        (x, y) => nearest(x, y, ratio),
+    };
+
+    let mut indexed_colors = Vec::new();
+    for index in 16..=255 {
+        let find = |theme: &Theme| {
+            theme
+                .indexed_colors
+                .iter()
+                .find(|indexed| indexed.index == index)
+                .map(|indexed| indexed.color)
+        };
+        let color = match (find(a), find(b)) {
+            (Some(x), Some(y)) => Some(mix(x, y)),
+            (x, y) => nearest(x, y, ratio),
+        };
+        indexed_colors.extend(color.and_then(|color| IndexedColor::new(index, color)));
+    }
+
+    Theme {
+        background: mix(a.background, b.background),
+        foreground: mix(a.foreground, b.foreground),
+        normal: mix_colors(&a.normal, &b.normal),
+        bright: mix_colors(&a.bright, &b.bright),
+        dim,
+        indexed_colors,
+        cursor: mix_pair(a.cursor, b.cursor),
+        vi_mode_cursor: mix_pair(a.vi_mode_cursor, b.vi_mode_cursor),
+        selection: mix_pair(a.selection, b.selection),
+        search_matches: mix_pair(a.search_matches, b.search_matches),
+        search_focused_match: mix_pair(a.search_focused_match, b.search_focused_match),
+        hints_start: mix_pair(a.hints_start, b.hints_start),
+        hints_end: mix_pair(a.hints_end, b.hints_end),
+        footer_bar: mix_pair(a.footer_bar, b.footer_bar),
+        line_indicator: mix_pair(a.line_indicator, b.line_indicator),
+    }
+}
+
+/// Whichever of `a` and `b` is closer to `ratio`.
+fn nearest<T>(a: T, b: T, ratio: f64) -> T {
+    if ratio < 0.5 {
+        a
+    } else {
+        b
+    }
+}
@@ -0,0 +1,365 @@
+use crate::color::{self, Oklch};
+use crate::config::ToHex;
+use crate::derive;
+use crate::schedule::Period;
+use crate::theme::Theme;
+use rgb::RGB8;
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+use termcolor::{Color, ColorSpec, WriteColor};
+
+/// WCAG 2 conformance level of text.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub enum Level {
+    /// Below 3:1, unreadable for many.
+    Fail,
+    /// At least 3:1, enough for large or bold text only.
+    AaLarge,
+    /// At least 4.5:1.
+    Aa,
+    /// At least 7:1.
+    Aaa,
+}
+
+impl Level {
+    pub fn of(contrast: f64) -> Self {
+        if contrast >= 7.0 {
+            Self::Aaa
+        } else if contrast >= 4.5 {
+            Self::Aa
+        } else if contrast >= 3.0 {
+            Self::AaLarge
+        } else {
+            Self::Fail
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Fail => "fail",
+            Self::AaLarge => "AA large",
+            Self::Aa => "AA",
+            Self::Aaa => "AAA",
+        })
+    }
+}
+
+impl FromStr for Level {
+    type Err = LevelParseError;
+
+    /// Parse a level that can be required of a theme.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "aa" => Ok(Self::Aa),
+            "aaa" => Ok(Self::Aaa),
+            _ => Err(LevelParseError(s.to_owned())),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct LevelParseError(String);
+
+impl fmt::Display for LevelParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown level '{}'; expected aa or aaa", self.0)
+    }
+}
+
+/// A color of a theme, checked against its background.
+pub struct Entry {
+    /// Key of the color under `colors`, e.g. `normal.red`.
+    pub name: String,
+    pub color: RGB8,
+    /// WCAG 2 contrast ratio.
+    pub contrast: f64,
+    /// APCA lightness contrast.
+    pub apca: f64,
+    /// Whether the color is the shade of the background, such as black in a
+    /// dark theme, which isn't meant to be readable on it.
+    pub background_shade: bool,
+}
+
+impl Entry {
+    pub fn level(&self) -> Level {
+        Level::of(self.contrast)
+    }
+
+    /// Whether the color is too hard to read, requiring `level`.
+    pub fn flagged(&self, level: Level) -> bool {
+        !self.background_shade && self.level() < level
+    }
+}
+
+/// Name of the color that is the shade of the background of `theme`.
+fn background_shade(theme: &Theme) -> &'static str {
+    match derive::period(theme) {
+        Period::Dark => "normal.black",
+        Period::Light => "normal.white",
+    }
+}
+
+/// Check the foreground and the normal, bright and dim colors of `theme`.
+pub fn check(theme: &Theme) -> Vec<Entry> {
+    let background = theme.background;
+    let shade = background_shade(theme);
+
+    let mut colors = vec![("foreground".to_owned(), theme.foreground)];
+    let sections = [
+        ("normal", Some(&theme.normal)),
+        ("bright", Some(&theme.bright)),
+        ("dim", theme.dim.as_ref()),
+    ];
+    for &(section, ref palette) in &sections {
+        if let Some(palette) = palette {
+            for &(name, color) in &palette.named() {
+                colors.push((format!("{}.{}", section, name), color));
+            }
+        }
+    }
+
+    colors
+        .into_iter()
+        .map(|(name, color)| Entry {
+            background_shade: name == shade,
+            contrast: color::contrast(color, background),
+            apca: color::apca(color, background),
+            name,
+            color,
+        })
+        .collect()
+}
+
+/// A color changed by `ensure_contrast`.
+pub struct Adjustment {
+    pub name: String,
+    pub old: RGB8,
+    pub new: RGB8,
+    /// Contrast before and after the change.
+    pub contrast: (f64, f64),
+}
+
+/// Change the lightness of the foreground and the normal and bright colors
+/// of `theme` that have less than `ratio` contrast with the background, just
+/// enough to reach it, keeping their hue. The shade of the background is
+/// left alone.
+pub fn ensure_contrast(theme: &mut Theme, ratio: f64) -> Vec<Adjustment> {
+    let background = theme.background;
+    let shade = background_shade(theme);
+    let mut adjustments = Vec::new();
+
+    let mut fix = |name: String, color: &mut RGB8| {
+        let old_contrast = color::contrast(*color, background);
+        if old_contrast >= ratio || name == shade {
+            return;
+        }
+
+        // try the side of the background the color is on first, and the
+        // other one if there's not enough room there
+        let lighter = color::luminance(*color) > color::luminance(background);
+        let lch = Oklch::from(*color);
+        let mut new = lch.with_contrast(background, ratio, lighter);
+        if color::contrast(new, background) < ratio {
+            let other = lch.with_contrast(background, ratio, !lighter);
+            if color::contrast(other, background) > color::contrast(new, background) {
+                new = other;
+            }
+        }
+
+        adjustments.push(Adjustment {
+            name,
+            old: *color,
+            new,
+            contrast: (old_contrast, color::contrast(new, background)),
+        });
+        *color = new;
+    };
+
+    fix("foreground".to_owned(), &mut theme.foreground);
+    for (section, palette) in
+        [("normal", &mut theme.normal), ("bright", &mut theme.bright)].iter_mut()
+    {
+        for (name, color) in palette.named_mut().iter_mut() {
+            fix(format!("{}.{}", section, name), color);
+        }
+    }
+
+    adjustments
+}
+
+/// Report the changes made by `ensure_contrast`.
+pub fn print_adjustments<W: WriteColor>(
+    out: &mut W,
+    adjustments: &[Adjustment],
+    ratio: f64,
+) -> io::Result<()> {
+    if adjustments.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(
+        out,
+        "Raised the contrast of {} {} to {}:1:",
+        adjustments.len(),
+        if adjustments.len() == 1 {
+            "color"
+        } else {
+            "colors"
+        },
+        ratio
+    )?;
+    for adjustment in adjustments {
+        write!(
+            out,
+            "  {:<16} {} -> {}  ({:.2}:1 -> {:.2}:1)",
+            adjustment.name,
+            adjustment.old.to_hex(),
+            adjustment.new.to_hex(),
+            adjustment.contrast.0,
+            adjustment.contrast.1
+        )?;
+        if adjustment.contrast.1 < ratio {
+            out.set_color(ColorSpec::new().set_fg(Some(Color::Red)))?;
+            write!(out, " as high as it goes")?;
+            out.reset()?;
+        }
+        writeln!(out)?;
+    }
+
+    out.flush()
+}
+
+/// Print the entries as a table, marking the ones below `level`.
+pub fn print_table<W: WriteColor>(
+    out: &mut W,
+    name: &str,
+    theme: &Theme,
+    entries: &[Entry],
+    level: Level,
+) -> io::Result<()> {
+    let bg = theme.background;
+    write!(out, "Contrast of ")?;
+    out.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)))?;
+    write!(out, "{}", name)?;
+    out.reset()?;
+    writeln!(out, " against its background, {}\n", bg.to_hex())?;
+
+    out.set_color(ColorSpec::new().set_bold(true))?;
+    writeln!(
+        out,
+        "{:<16} {:<7}  {:<8}  {:>8}  {:<8}  {:>6}",
+        "color", "", "sample", "contrast", "WCAG", "APCA"
+    )?;
+    out.reset()?;
+
+    for entry in entries {
+        write!(out, "{:<16} {}  ", entry.name, entry.color.to_hex())?;
+
+        let (c, b) = (entry.color, bg);
+        out.set_color(
+            ColorSpec::new()
+                .set_fg(Some(Color::Rgb(c.r, c.g, c.b)))
+                .set_bg(Some(Color::Rgb(b.r, b.g, b.b))),
+        )?;
+        write!(out, " Sample ")?;
+        out.reset()?;
+
+        write!(out, "  {:>6.2}:1  ", entry.contrast)?;
+
+        let mut spec = ColorSpec::new();
+        if entry.flagged(level) {
+            spec.set_fg(Some(Color::Red)).set_bold(true);
+        } else if !entry.background_shade {
+            spec.set_fg(Some(Color::Green));
+        }
+        out.set_color(&spec)?;
+        write!(out, "{:<8}", entry.level().to_string())?;
+        out.reset()?;
+
+        write!(out, "  {:>6.1}", entry.apca)?;
+        if entry.background_shade {
+            write!(out, "  (background shade)")?;
+        }
+        writeln!(out)?;
+    }
+
+    let flagged = entries.iter().filter(|entry| entry.flagged(level)).count();
+    writeln!(out)?;
+    if flagged == 0 {
+        out.set_color(ColorSpec::new().set_fg(Some(Color::Green)).set_bold(true))?;
+        write!(out, "All colors pass {}", level)?;
+        out.reset()?;
+        writeln!(out)?;
+    } else {
+        out.set_color(ColorSpec::new().set_fg(Some(Color::Red)).set_bold(true))?;
+        write!(
+            out,
+            "{} of {} colors are below {}",
+            flagged,
+            entries.len(),
+            level
+        )?;
+        out.reset()?;
+        writeln!(out)?;
+    }
+
+    out.flush()
+}
+
+/// Print the entries as JSON, for scripts and CI.
+pub fn print_json<W: Write>(
+    out: &mut W,
+    name: &str,
+    theme: &Theme,
+    entries: &[Entry],
+    level: Level,
+) -> io::Result<()> {
+    writeln!(out, "{{")?;
+    writeln!(out, "  \"theme\": {},", json_string(name))?;
+    writeln!(out, "  \"background\": \"{}\",", theme.background.to_hex())?;
+    writeln!(out, "  \"level\": \"{}\",", level)?;
+    writeln!(
+        out,
+        "  \"passed\": {},",
+        !entries.iter().any(|entry| entry.flagged(level))
+    )?;
+    writeln!(out, "  \"colors\": [")?;
+    for (i, entry) in entries.iter().enumerate() {
+        writeln!(
+            out,
+            "    {{\"name\": \"{}\", \"color\": \"{}\", \"contrast\": {:.2}, \"apca\": {:.1}, \
+             \"level\": \"{}\", \"background_shade\": {}, \"flagged\": {}}}{}",
+            entry.name,
+            entry.color.to_hex(),
+            entry.contrast,
+            entry.apca,
+            entry.level(),
+            entry.background_shade,
+            entry.flagged(level),
+            if i + 1 < entries.len() { "," } else { "" }
+        )?;
+    }
+    writeln!(out, "  ]")?;
+    writeln!(out, "}}")?;
+
+    out.flush()
+}
+
+/// Quote `s` as a JSON string.
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -0,0 +1,331 @@
+use rgb::RGB8;
+
+/// Decode an sRGB channel to linear light, between 0 and 1.
+pub fn to_linear(c: u8) -> f64 {
+    let c = f64::from(c) / 255.0;
+
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encode linear light as an sRGB channel, clamping it to the gamut.
+pub fn from_linear(c: f64) -> u8 {
+    let c = if c <= 0.003_130_8 {
+        12.92 * c
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+
+    (c.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// A color in OKLab, a perceptual color space: unlike in HSL, changing the hue
+/// or chroma of a color keeps its perceived lightness.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Oklab {
+    /// Perceived lightness, between 0 (black) and 1 (white).
+    pub l: f64,
+    pub a: f64,
+    pub b: f64,
+}
+
+impl Oklab {
+    /// Linear sRGB of this color, which may be out of gamut.
+    fn to_linear_rgb(self) -> [f64; 3] {
+        let l = (self.l + 0.396_337_777_4 * self.a + 0.215_803_757_3 * self.b).powi(3);
+        let m = (self.l - 0.105_561_345_8 * self.a - 0.063_854_172_8 * self.b).powi(3);
+        let s = (self.l - 0.089_484_177_5 * self.a - 1.291_485_548_0 * self.b).powi(3);
+
+        [
+            4.076_741_662_1 * l - 3.307_711_591_3 * m + 0.230_969_929_2 * s,
+            -1.268_438_004_6 * l + 2.609_757_401_1 * m - 0.341_319_396_5 * s,
+            -0.004_196_086_3 * l - 0.703_418_614_7 * m + 1.707_614_701_0 * s,
+        ]
+    }
+}
+
+/// The color `t` of the way from `a` to `b`, in OKLab, so that the colors in
+/// between change evenly in lightness.
+pub fn mix(a: RGB8, b: RGB8, t: f64) -> RGB8 {
+    let (a, b) = (Oklab::from(a), Oklab::from(b));
+    let lab = Oklab {
+        l: a.l + (b.l - a.l) * t,
+        a: a.a + (b.a - a.a) * t,
+        b: a.b + (b.b - a.b) * t,
+    };
+
+    Oklch::from(lab).to_rgb8()
+}
+
+impl From<RGB8> for Oklab {
+    fn from(rgb: RGB8) -> Self {
+        let (r, g, b) = (to_linear(rgb.r), to_linear(rgb.g), to_linear(rgb.b));
+
+        let l = (0.412_221_470_8 * r + 0.536_332_536_3 * g + 0.051_445_992_9 * b).cbrt();
+        let m = (0.211_903_498_2 * r + 0.680_699_545_1 * g + 0.107_396_956_6 * b).cbrt();
+        let s = (0.088_302_461_9 * r + 0.281_718_837_6 * g + 0.629_978_700_5 * b).cbrt();
+
+        Self {
+            l: 0.210_454_255_3 * l + 0.793_617_785_0 * m - 0.004_072_046_8 * s,
+            a: 1.977_998_495_1 * l - 2.428_592_205_0 * m + 0.450_593_709_9 * s,
+            b: 0.025_904_037_1 * l + 0.782_771_766_2 * m - 0.808_675_766_0 * s,
+        }
+    }
+}
+
+/// OKLab in polar form.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Oklch {
+    /// Perceived lightness, between 0 (black) and 1 (white).
+    pub l: f64,
+    /// Colorfulness, 0 for grays and up to about 0.37 in sRGB.
+    pub c: f64,
+    /// Hue angle in degrees.
+    pub h: f64,
+}
+
+impl Oklch {
+    fn to_oklab(self, c: f64) -> Oklab {
+        Oklab {
+            l: self.l,
+            a: c * self.h.to_radians().cos(),
+            b: c * self.h.to_radians().sin(),
+        }
+    }
+
+    /// The closest sRGB color. Colors outside of the gamut lose chroma until
+    /// they fit, which keeps their lightness and hue.
+    pub fn to_rgb8(self) -> RGB8 {
+        let l = self.l.clamp(0.0, 1.0);
+        let color = Self { l, ..self };
+        let in_gamut = |c: f64| {
+            color
+                .to_oklab(c)
+                .to_linear_rgb()
+                .iter()
+                .all(|&x| (-1e-4..=1.0 + 1e-4).contains(&x))
+        };
+
+        let c = if in_gamut(self.c) {
+            self.c
+        } else {
+            // binary search for the largest chroma in gamut
+            let (mut low, mut high) = (0.0, self.c);
+            for _ in 0..20 {
+                let mid = (low + high) / 2.0;
+                if in_gamut(mid) {
+                    low = mid;
+                } else {
+                    high = mid;
+                }
+            }
+            low
+        };
+
+        let [r, g, b] = color.to_oklab(c).to_linear_rgb();
+        RGB8::new(from_linear(r), from_linear(g), from_linear(b))
+    }
+
+    /// The color with this hue and chroma whose lightness is closest to that
+    /// of `background` while still reaching a contrast of `ratio` with it,
+    /// on the lighter or darker side of it. If `ratio` can't be reached, the
+    /// lightest or darkest color is used.
+    pub fn with_contrast(self, background: RGB8, ratio: f64, lighter: bool) -> RGB8 {
+        let with_l = |l: f64| Self { l, ..self }.to_rgb8();
+
+        // contrast grows with the distance from the background's lightness
+        let mut near = Self::from(background).l;
+        let mut far = if lighter { 1.0 } else { 0.0 };
+        if contrast(with_l(far), background) < ratio {
+            return with_l(far);
+        }
+        for _ in 0..20 {
+            let mid = (near + far) / 2.0;
+            if contrast(with_l(mid), background) >= ratio {
+                far = mid;
+            } else {
+                near = mid;
+            }
+        }
+
+        with_l(far)
+    }
+}
+
+impl From<Oklab> for Oklch {
+    fn from(lab: Oklab) -> Self {
+        Self {
+            l: lab.l,
+            c: lab.a.hypot(lab.b),
+            h: lab.b.atan2(lab.a).to_degrees().rem_euclid(360.0),
+        }
+    }
+}
+
+impl From<RGB8> for Oklch {
+    fn from(rgb: RGB8) -> Self {
+        Self::from(Oklab::from(rgb))
+    }
+}
+
+/// Relative luminance as defined by WCAG 2, between 0 and 1.
+pub fn luminance(rgb: RGB8) -> f64 {
+    0.2126 * to_linear(rgb.r) + 0.7152 * to_linear(rgb.g) + 0.0722 * to_linear(rgb.b)
+}
+
+/// WCAG 2 contrast ratio between two colors, from 1 (none) to 21 (black on
+/// white).
+pub fn contrast(a: RGB8, b: RGB8) -> f64 {
+    let (a, b) = (luminance(a), luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// APCA lightness contrast (Lc) of `text` on `background`, as in APCA-W3
+/// 0.0.98G. It's positive for dark text on a light background and negative
+/// the other way around; about 75 is needed for body text and 90 is
+/// preferred.
+pub fn apca(text: RGB8, background: RGB8) -> f64 {
+    let y = |rgb: RGB8| {
+        let channel = |c: u8| (f64::from(c) / 255.0).powf(2.4);
+        let y = 0.212_672_9 * channel(rgb.r)
+            + 0.715_152_2 * channel(rgb.g)
+            + 0.072_175 * channel(rgb.b);
+
+        // soft clamp near black
+        if y < 0.022 {
+            y + (0.022 - y).powf(1.414)
+        } else {
+            y
+        }
+    };
+    let (text, background) = (y(text), y(background));
+
+    if (background - text).abs() < 0.0005 {
+        return 0.0;
+    }
+
+    let lc = if background > text {
+        let sapc = (background.powf(0.56) - text.powf(0.57)) * 1.14;
+        if sapc < 0.1 {
+            0.0
+        } else {
+            sapc - 0.027
+        }
+    } else {
+        let sapc = (background.powf(0.65) - text.powf(0.62)) * 1.14;
+        if sapc > -0.1 {
+            0.0
+        } else {
+            sapc + 0.027
+        }
+    };
+
+    lc * 100.0
+}
+
+/// A color in CIELAB, under the D65 white point of sRGB.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Lab {
+    pub l: f64,
+    pub a: f64,
+    pub b: f64,
+}
+
+impl From<RGB8> for Lab {
+    fn from(rgb: RGB8) -> Self {
+        let (r, g, b) = (to_linear(rgb.r), to_linear(rgb.g), to_linear(rgb.b));
+
+        // XYZ, relative to the white point
+        let x = (0.412_456_4 * r + 0.357_576_1 * g + 0.180_437_5 * b) / 0.950_47;
+        let y = 0.212_672_9 * r + 0.715_152_2 * g + 0.072_175 * b;
+        let z = (0.019_333_9 * r + 0.119_192 * g + 0.950_304_1 * b) / 1.088_83;
+
+        let f = |t: f64| {
+            if t > 216.0 / 24389.0 {
+                t.cbrt()
+            } else {
+                (24389.0 / 27.0 * t + 16.0) / 116.0
+            }
+        };
+        let (x, y, z) = (f(x), f(y), f(z));
+
+        Self {
+            l: 116.0 * y - 16.0,
+            a: 500.0 * (x - y),
+            b: 200.0 * (y - z),
+        }
+    }
+}
+
+/// CIEDE2000 color difference. Around 1 is just noticeable side by side,
+/// and colors less than about 10 apart are easily mistaken for each other.
+pub fn delta_e(a: RGB8, b: RGB8) -> f64 {
+    Lab::from(a).delta_e(Lab::from(b))
+}
+
+impl Lab {
+    /// CIEDE2000 difference from `other`.
+    pub fn delta_e(self, other: Self) -> f64 {
+        let (lab1, lab2) = (self, other);
+
+        let c_mean = (lab1.a.hypot(lab1.b) + lab2.a.hypot(lab2.b)) / 2.0;
+        let g = 0.5 * (1.0 - (c_mean.powi(7) / (c_mean.powi(7) + 25f64.powi(7))).sqrt());
+
+        let prime = |lab: Lab| {
+            let a = (1.0 + g) * lab.a;
+            let c = a.hypot(lab.b);
+            let h = if c == 0.0 {
+                0.0
+            } else {
+                lab.b.atan2(a).to_degrees().rem_euclid(360.0)
+            };
+            (c, h)
+        };
+        let (c1, h1) = prime(lab1);
+        let (c2, h2) = prime(lab2);
+
+        let dl = lab2.l - lab1.l;
+        let dc = c2 - c1;
+        let dh = if c1 * c2 == 0.0 {
+            0.0
+        } else if (h2 - h1).abs() <= 180.0 {
+            h2 - h1
+        } else if h2 <= h1 {
+            h2 - h1 + 360.0
+        } else {
+            h2 - h1 - 360.0
+        };
+        let dh = 2.0 * (c1 * c2).sqrt() * (dh / 2.0).to_radians().sin();
+
+        let l_mean = (lab1.l + lab2.l) / 2.0;
+        let c_mean = (c1 + c2) / 2.0;
+        let h_mean = if c1 * c2 == 0.0 {
+            h1 + h2
+        } else if (h1 - h2).abs() <= 180.0 {
+            (h1 + h2) / 2.0
+        } else if h1 + h2 < 360.0 {
+            (h1 + h2 + 360.0) / 2.0
+        } else {
+            (h1 + h2 - 360.0) / 2.0
+        };
+
+        let t = 1.0 - 0.17 * (h_mean - 30.0).to_radians().cos()
+            + 0.24 * (2.0 * h_mean).to_radians().cos()
+            + 0.32 * (3.0 * h_mean + 6.0).to_radians().cos()
+            - 0.20 * (4.0 * h_mean - 63.0).to_radians().cos();
+        let sl = 1.0 + 0.015 * (l_mean - 50.0).powi(2) / (20.0 + (l_mean - 50.0).powi(2)).sqrt();
+        let sc = 1.0 + 0.045 * c_mean;
+        let sh = 1.0 + 0.015 * c_mean * t;
+        let rt = -2.0
+            * (c_mean.powi(7) / (c_mean.powi(7) + 25f64.powi(7))).sqrt()
+            * (60.0 * (-((h_mean - 275.0) / 25.0).powi(2)).exp())
+                .to_radians()
+                .sin();
+
+        ((dl / sl).powi(2) + (dc / sc).powi(2) + (dh / sh).powi(2) + rt * (dc / sc) * (dh / sh))
+            .sqrt()
+    }
+}
@@ -0,0 +1,424 @@
+use crate::color::Oklch;
+use rgb::RGB8;
+use std::fmt;
+
+/// Parse a color written in one of these forms:
+///
+/// - `#rgb`, `#rrggbb` or `0xrrggbb`
+/// - `rgb(r, g, b)`, with channels from 0 to 255 or percentages
+/// - `hsl(h, s%, l%)`
+/// - `oklch(l c h)`, with the lightness from 0 to 1 or a percentage
+/// - a CSS color name, which are the X11 ones with a few exceptions, such as
+///   `rebeccapurple` or `light goldenrod yellow`
+/// - an index of the xterm 256 color palette, e.g. `208`
+///
+/// The arguments of the functions can be separated by commas or spaces.
+pub fn parse_color(s: &str) -> Result<RGB8, ColorParseError> {
+    let s = s.trim();
+    let invalid = || ColorParseError(s.to_owned());
+
+    if let Some(digits) = s.strip_prefix('#').or_else(|| s.strip_prefix("0x")) {
+        return hex(digits).ok_or_else(invalid);
+    }
+    if let Ok(index) = s.parse::<u8>() {
+        return Ok(xterm(index));
+    }
+    if let Some(open) = s.find('(') {
+        let args = s[open + 1..].strip_suffix(')').ok_or_else(invalid)?;
+        let args: Vec<&str> = args
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|arg| !arg.is_empty())
+            .collect();
+        let args = match args[..] {
+            [a, b, c] => [a, b, c],
+            _ => return Err(invalid()),
+        };
+
+        let color = match s[..open].trim().to_ascii_lowercase().as_str() {
+            "rgb" => rgb(args),
+            "hsl" => hsl(args),
+            "oklch" => oklch(args),
+            _ => None,
+        };
+        return color.ok_or_else(invalid);
+    }
+
+    named(s).ok_or_else(invalid)
+}
+
+/// `rrggbb` or `rgb`, where every digit is doubled.
+fn hex(digits: &str) -> Option<RGB8> {
+    let digits = match digits.len() {
+        3 => digits.chars().flat_map(|c| [c, c]).collect(),
+        6 => digits.to_owned(),
+        _ => return None,
+    };
+    let mut rgb = [0; 3];
+    hex::decode_to_slice(digits, &mut rgb).ok()?;
+
+    Some(RGB8::new(rgb[0], rgb[1], rgb[2]))
+}
+
+fn rgb(args: [&str; 3]) -> Option<RGB8> {
+    let channel = |arg: &str| match arg.strip_suffix('%') {
+        Some(percent) => fraction(percent).map(|f| (f * 255.0).round() as u8),
+        None => arg.parse().ok(),
+    };
+
+    Some(RGB8::new(
+        channel(args[0])?,
+        channel(args[1])?,
+        channel(args[2])?,
+    ))
+}
+
+fn hsl(args: [&str; 3]) -> Option<RGB8> {
+    let h = degrees(args[0])?;
+    let s = fraction(args[1].strip_suffix('%').unwrap_or(args[1]))?;
+    let l = fraction(args[2].strip_suffix('%').unwrap_or(args[2]))?;
+
+    let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let sector = h / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (r, g, b) = match sector as u8 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = l - chroma / 2.0;
+    let channel = |c: f64| ((c + m) * 255.0).round() as u8;
+
+    Some(RGB8::new(channel(r), channel(g), channel(b)))
+}
+
+fn oklch(args: [&str; 3]) -> Option<RGB8> {
+    let l = match args[0].strip_suffix('%') {
+        Some(percent) => fraction(percent)?,
+        None => args[0].parse().ok().filter(|l| (0.0..=1.0).contains(l))?,
+    };
+    let c: f64 = args[1].parse().ok().filter(|c: &f64| *c >= 0.0)?;
+
+    Some(
+        Oklch {
+            l,
+            c,
+            h: degrees(args[2])?,
+        }
+        .to_rgb8(),
+    )
+}
+
+/// A percentage from 0 to 100, without the `%`, as a fraction.
+fn fraction(percent: &str) -> Option<f64> {
+    let percent: f64 = percent.parse().ok()?;
+    (0.0..=100.0).contains(&percent).then(|| percent / 100.0)
+}
+
+/// An angle, optionally followed by `deg`, from 0 up to 360.
+fn degrees(s: &str) -> Option<f64> {
+    let degrees: f64 = s.strip_suffix("deg").unwrap_or(s).parse().ok()?;
+    degrees.is_finite().then(|| degrees.rem_euclid(360.0))
+}
+
+/// Color `index` of xterm's 256 color palette.
+fn xterm(index: u8) -> RGB8 {
+    /// The 16 colors xterm uses unless configured otherwise.
+    const SYSTEM: [u32; 16] = [
+        0x000000, 0xcd0000, 0x00cd00, 0xcdcd00, 0x0000ee, 0xcd00cd, 0x00cdcd, 0xe5e5e5, 0x7f7f7f,
+        0xff0000, 0x00ff00, 0xffff00, 0x5c5cff, 0xff00ff, 0x00ffff, 0xffffff,
+    ];
+    /// Levels of the channels of the 6×6×6 color cube.
+    const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    match index {
+        0..=15 => from_u32(SYSTEM[usize::from(index)]),
+        16..=231 => {
+            let i = index - 16;
+            RGB8::new(
+                CUBE[usize::from(i / 36)],
+                CUBE[usize::from(i / 6 % 6)],
+                CUBE[usize::from(i % 6)],
+            )
+        }
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            RGB8::new(gray, gray, gray)
+        }
+    }
+}
+
+/// The CSS color `name`. Case and spaces are ignored, as X11 does.
+fn named(name: &str) -> Option<RGB8> {
+    let name: String = name
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+
+    NAMES
+        .binary_search_by(|&(n, _)| n.cmp(&name))
+        .ok()
+        .map(|i| from_u32(NAMES[i].1))
+}
+
+const fn from_u32(rgb: u32) -> RGB8 {
+    RGB8::new((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)
+}
+
+/// The CSS named colors, sorted by name.
+const NAMES: [(&str, u32); 148] = [
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
+
+#[derive(Debug)]
+pub struct ColorParseError(String);
+
+impl fmt::Display for ColorParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid color '{}'; expected #rrggbb, rgb(…), hsl(…), oklch(…), a color name or \
+             an xterm color index",
+            self.0
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Option<RGB8> {
+        parse_color(s).ok()
+    }
+
+    #[test]
+    fn hex() {
+        assert_eq!(parse("#ff8000"), Some(RGB8::new(0xff, 0x80, 0x00)));
+        assert_eq!(parse("#FF8000"), Some(RGB8::new(0xff, 0x80, 0x00)));
+        assert_eq!(parse("#f80"), Some(RGB8::new(0xff, 0x88, 0x00)));
+        assert_eq!(parse("0x0000ff"), Some(RGB8::new(0, 0, 0xff)));
+        assert_eq!(parse("  #ffffff "), Some(RGB8::new(0xff, 0xff, 0xff)));
+
+        assert_eq!(parse("#ff80"), None);
+        assert_eq!(parse("#gggggg"), None);
+        assert_eq!(parse("0x"), None);
+    }
+
+    #[test]
+    fn xterm_index() {
+        assert_eq!(parse("0"), Some(RGB8::new(0, 0, 0)));
+        assert_eq!(parse("9"), Some(RGB8::new(0xff, 0, 0)));
+        assert_eq!(parse("16"), Some(RGB8::new(0, 0, 0)));
+        assert_eq!(parse("196"), Some(RGB8::new(0xff, 0, 0)));
+        assert_eq!(parse("208"), Some(RGB8::new(0xff, 0x87, 0)));
+        assert_eq!(parse("231"), Some(RGB8::new(0xff, 0xff, 0xff)));
+        assert_eq!(parse("232"), Some(RGB8::new(8, 8, 8)));
+        assert_eq!(parse("255"), Some(RGB8::new(0xee, 0xee, 0xee)));
+
+        assert_eq!(parse("256"), None);
+        assert_eq!(parse("-1"), None);
+    }
+
+    #[test]
+    fn rgb_function() {
+        assert_eq!(parse("rgb(255, 128, 0)"), Some(RGB8::new(255, 128, 0)));
+        assert_eq!(parse("rgb(255 128 0)"), Some(RGB8::new(255, 128, 0)));
+        assert_eq!(parse("RGB(100%, 50%, 0%)"), Some(RGB8::new(255, 128, 0)));
+
+        assert_eq!(parse("rgb(256, 0, 0)"), None);
+        assert_eq!(parse("rgb(101%, 0, 0)"), None);
+        assert_eq!(parse("rgb(1, 2)"), None);
+        assert_eq!(parse("rgb(1, 2, 3, 4)"), None);
+        assert_eq!(parse("rgb(1, 2, 3"), None);
+    }
+
+    #[test]
+    fn hsl_function() {
+        assert_eq!(parse("hsl(0, 100%, 50%)"), Some(RGB8::new(255, 0, 0)));
+        assert_eq!(parse("hsl(120deg 100% 25%)"), Some(RGB8::new(0, 128, 0)));
+        assert_eq!(parse("hsl(240, 100%, 50%)"), Some(RGB8::new(0, 0, 255)));
+        assert_eq!(parse("hsl(360, 100%, 50%)"), Some(RGB8::new(255, 0, 0)));
+        assert_eq!(parse("hsl(0, 0%, 100%)"), Some(RGB8::new(255, 255, 255)));
+
+        assert_eq!(parse("hsl(0, 100%, 150%)"), None);
+        assert_eq!(parse("hsl(red, 100%, 50%)"), None);
+    }
+
+    #[test]
+    fn oklch_function() {
+        assert_eq!(parse("oklch(0 0 0)"), Some(RGB8::new(0, 0, 0)));
+        assert_eq!(parse("oklch(100% 0 0)"), Some(RGB8::new(255, 255, 255)));
+        assert_eq!(parse("oklch(1, 0, 0)"), parse("oklch(100% 0 0deg)"));
+
+        assert_eq!(parse("oklch(1.5 0 0)"), None);
+        assert_eq!(parse("oklch(0.5 -0.1 0)"), None);
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(parse("rebeccapurple"), Some(RGB8::new(0x66, 0x33, 0x99)));
+        assert_eq!(
+            parse("Light Goldenrod Yellow"),
+            Some(RGB8::new(0xfa, 0xfa, 0xd2))
+        );
+        assert_eq!(parse("notacolor"), None);
+        assert_eq!(parse("foo(1, 2, 3)"), None);
+    }
+
+    #[test]
+    fn names_are_sorted() {
+        // named() relies on it to binary search
+        assert!(NAMES.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+}
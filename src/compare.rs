@@ -0,0 +1,315 @@
+use crate::color;
+use crate::config::ToHex;
+use crate::slot::PAIRS;
+use crate::theme::{CellColor, ColorPair, Theme};
+use rgb::RGB8;
+use std::io;
+use termcolor::{Color, ColorSpec, WriteColor};
+use tui::backend::Backend;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::{self, Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Paragraph};
+use tui::Frame;
+
+/// Number of slots marked as the largest differences.
+const LARGEST: usize = 3;
+/// Slots that differ less than this aren't marked, even if they're among
+/// the largest differences. About the smallest difference people notice.
+const MIN_MARKED: f64 = 2.0;
+/// Width of a swatch followed by a hex code, or of `CellForeground`.
+const SWATCH_WIDTH: usize = 14;
+
+/// How much the color of a slot differs between two themes.
+#[derive(Debug, Clone)]
+pub struct Difference {
+    pub name: String,
+    /// The color of the slot in each theme, or `None` if it doesn't set it.
+    pub colors: (Option<CellColor>, Option<CellColor>),
+    /// `None` unless both themes set the slot, to colors that can be
+    /// compared: a fixed color can't be compared to that of the cell.
+    pub delta_e: Option<f64>,
+    /// Among the largest differences between the themes.
+    pub largest: bool,
+}
+
+impl Difference {
+    fn new(name: String, colors: (Option<CellColor>, Option<CellColor>)) -> Self {
+        let delta_e = match colors {
+            (Some(CellColor::Rgb(a)), Some(CellColor::Rgb(b))) => Some(color::delta_e(a, b)),
+            (Some(a), Some(b)) if a == b => Some(0.0),
+            _ => None,
+        };
+
+        Self {
+            name,
+            colors,
+            delta_e,
+            largest: false,
+        }
+    }
+
+    /// The ΔE, or else which of the themes named `names` sets the slot.
+    fn status(&self, names: (&str, &str)) -> String {
+        match (self.delta_e, self.colors) {
+            (Some(delta_e), _) => format!("{:>5.1}", delta_e),
+            (None, (Some(_), None)) => format!("only in {}", names.0),
+            (None, (None, Some(_))) => format!("only in {}", names.1),
+            _ => format!("{:>5}", "-"),
+        }
+    }
+}
+
+/// Compare every color of `a` and `b`. Dim colors a theme doesn't have are
+/// derived from its normal colors, as alacritty does. Slots of the color
+/// pairs and indexed colors that only one theme sets are listed as such, and
+/// those neither sets are left out.
+pub fn compare(a: &Theme, b: &Theme) -> Vec<Difference> {
+    let rgb = |color: RGB8| Some(CellColor::Rgb(color));
+
+    let mut differences = vec![
+        Difference::new(
+            "primary.background".to_owned(),
+            (rgb(a.background), rgb(b.background)),
+        ),
+        Difference::new(
+            "primary.foreground".to_owned(),
+            (rgb(a.foreground), rgb(b.foreground)),
+        ),
+    ];
+
+    let dim = |theme: &Theme| theme.dim.unwrap_or_else(|| theme.normal.dimmed());
+    let palettes = [
+        ("normal", (a.normal, b.normal)),
+        ("bright", (a.bright, b.bright)),
+        ("dim", (dim(a), dim(b))),
+    ];
+    for &(section, (palette_a, palette_b)) in &palettes {
+        for (&(name, color_a), &(_, color_b)) in palette_a.named().iter().zip(&palette_b.named()) {
+            differences.push(Difference::new(
+                format!("{}.{}", section, name),
+                (rgb(color_a), rgb(color_b)),
+            ));
+        }
+    }
+
+    let pairs = (a.color_pairs(), b.color_pairs());
+    let pairs = pairs.0.iter().zip(&pairs.1);
+    for (&(section, (foreground, background)), (pair_a, pair_b)) in PAIRS.iter().zip(pairs) {
+        let section = section.trim_start_matches("colors.");
+        for &(key, is_foreground) in &[(foreground, true), (background, false)] {
+            let color = |pair: ColorPair| {
+                if is_foreground {
+                    pair.foreground
+                } else {
+                    pair.background
+                }
+            };
+            let colors = (pair_a.and_then(color), pair_b.and_then(color));
+            if colors != (None, None) {
+                differences.push(Difference::new(format!("{}.{}", section, key), colors));
+            }
+        }
+    }
+
+    let mut indices: Vec<u8> = a
+        .indexed_colors
+        .iter()
+        .chain(&b.indexed_colors)
+        .map(|indexed| indexed.index)
+        .collect();
+    indices.sort_unstable();
+    indices.dedup();
+    for index in indices {
+        let color = |theme: &Theme| {
+            theme
+                .indexed_colors
+                .iter()
+                .find(|indexed| indexed.index == index)
+                .map(|indexed| CellColor::Rgb(indexed.color))
+        };
+        differences.push(Difference::new(
+            format!("indexed_colors.{}", index),
+            (color(a), color(b)),
+        ));
+    }
+
+    let mut order: Vec<usize> = (0..differences.len())
+        .filter(|&i| differences[i].delta_e.is_some())
+        .collect();
+    order.sort_by(|&i, &j| {
+        differences[j]
+            .delta_e
+            .partial_cmp(&differences[i].delta_e)
+            .expect("ΔE isn't NaN")
+    });
+    for &i in order.iter().take(LARGEST) {
+        differences[i].largest = differences[i].delta_e >= Some(MIN_MARKED);
+    }
+
+    differences
+}
+
+/// Width of the column of slot names.
+fn name_width(differences: &[Difference]) -> usize {
+    differences
+        .iter()
+        .map(|difference| difference.name.len())
+        .max()
+        .unwrap_or_default()
+}
+
+/// Print `differences` as a table with a swatch of both colors of every
+/// slot, marking the largest differences.
+pub fn print<W: WriteColor>(
+    out: &mut W,
+    names: (&str, &str),
+    differences: &[Difference],
+) -> io::Result<()> {
+    let name_width = name_width(differences);
+    let width = |name: &str| name.chars().count().max(SWATCH_WIDTH);
+    let widths = (width(names.0), width(names.1));
+
+    out.set_color(ColorSpec::new().set_bold(true))?;
+    writeln!(
+        out,
+        "{:<w$}  {:<w0$}  {:<w1$}  {:>5}",
+        "color",
+        names.0,
+        names.1,
+        "ΔE",
+        w = name_width,
+        w0 = widths.0,
+        w1 = widths.1
+    )?;
+    out.reset()?;
+
+    for difference in differences {
+        write!(out, "{:<w$}  ", difference.name, w = name_width)?;
+        swatch(out, difference.colors.0, widths.0)?;
+        swatch(out, difference.colors.1, widths.1)?;
+
+        let status = difference.status(names);
+        if difference.largest {
+            out.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)).set_bold(true))?;
+            write!(out, "{}  ◀ largest", status)?;
+            out.reset()?;
+        } else {
+            write!(out, "{}", status)?;
+        }
+        writeln!(out)?;
+    }
+
+    let compared: Vec<f64> = differences.iter().filter_map(|d| d.delta_e).collect();
+    let mean = compared.iter().sum::<f64>() / compared.len() as f64;
+    writeln!(out, "\nMean ΔE {:.1}", mean)?;
+
+    out.flush()
+}
+
+/// Print a swatch of `color` followed by its hex code, or the name of a
+/// color taken from the cell, padded to `width`.
+fn swatch<W: WriteColor>(out: &mut W, color: Option<CellColor>, width: usize) -> io::Result<()> {
+    match color {
+        Some(CellColor::Rgb(c)) => {
+            out.set_color(ColorSpec::new().set_bg(Some(Color::Rgb(c.r, c.g, c.b))))?;
+            write!(out, "      ")?;
+            out.reset()?;
+            write!(out, " {:<w$}  ", c.to_hex(), w = width - 7)
+        }
+        Some(cell) => write!(out, "{:<w$}  ", cell.to_string(), w = width),
+        None => write!(out, "{:<w$}  ", "-", w = width),
+    }
+}
+
+/// Draw the themes side by side in `area`, each on its own background, with
+/// the ΔE of every slot between them. `scroll` is the first row shown.
+pub fn draw<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    (names, themes): ((&str, &str), (&Theme, &Theme)),
+    differences: &[Difference],
+    scroll: u16,
+) {
+    let statuses: Vec<String> = differences.iter().map(|d| d.status(names)).collect();
+    // room for the borders and the marker
+    let status_width = statuses
+        .iter()
+        .map(|s| s.chars().count())
+        .max()
+        .unwrap_or(5)
+        + 4;
+
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage(50),
+                Constraint::Length(status_width as u16),
+                Constraint::Percentage(50),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+
+    let name_width = name_width(differences) + 1;
+    let rgb = |c: RGB8| style::Color::Rgb(c.r, c.g, c.b);
+    let panel = |name: &str, theme: &Theme, color: fn(&Difference) -> Option<CellColor>| {
+        let lines: Vec<Spans> = differences
+            .iter()
+            .map(|difference| {
+                let mut spans = vec![Span::raw(format!(
+                    "{:<w$}",
+                    difference.name,
+                    w = name_width
+                ))];
+                match color(difference) {
+                    Some(CellColor::Rgb(c)) => {
+                        spans.push(Span::styled("      ", Style::default().bg(rgb(c))));
+                        spans.push(Span::raw(format!(" {}", c.to_hex())));
+                    }
+                    Some(cell) => spans.push(Span::raw(cell.to_string())),
+                    None => spans.push(Span::raw("-")),
+                }
+                Spans::from(spans)
+            })
+            .collect();
+
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(name.to_owned())
+                    .borders(Borders::ALL),
+            )
+            .style(
+                Style::default()
+                    .fg(rgb(theme.foreground))
+                    .bg(rgb(theme.background)),
+            )
+            .scroll((scroll, 0))
+    };
+
+    let delta_e: Vec<Spans> = differences
+        .iter()
+        .zip(statuses)
+        .map(|(difference, status)| {
+            if difference.largest {
+                let style = Style::default()
+                    .fg(style::Color::Yellow)
+                    .add_modifier(Modifier::BOLD);
+                Spans::from(Span::styled(format!("{} ◀", status), style))
+            } else {
+                Spans::from(status)
+            }
+        })
+        .collect();
+
+    f.render_widget(panel(names.0, themes.0, |d| d.colors.0), chunks[0]);
+    f.render_widget(
+        Paragraph::new(delta_e)
+            .block(Block::default().title("ΔE").borders(Borders::ALL))
+            .scroll((scroll, 0)),
+        chunks[1],
+    );
+    f.render_widget(panel(names.1, themes.1, |d| d.colors.1), chunks[2]);
+}
@@ -1,28 +1,85 @@
-use crate::theme::{Font, FontSize, Theme, Window};
+use crate::color_syntax::parse_color;
+use crate::error::{Error, ErrorKind, Result};
+use crate::theme::{yaml_quote, IndexedColor, Theme, ThemeColors, Window};
+use crate::theme::{CellColor, Class, ColorPair, Decorations, Dimensions, Font, FontSize};
+use crate::theme::{Opacity, Padding, Position, StartupMode};
 use rgb::RGB8;
+use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::fmt;
-use yaml_rust::YamlLoader;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use yaml_rust::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust::scanner::{Marker, TScalarStyle, TokenType};
+use yaml_rust::yaml::Hash;
+use yaml_rust::{Yaml, YamlLoader};
 
+/// Syntax of a config file. alacritty switched from YAML to TOML in 0.13.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Format {
+    Yaml,
+    Toml,
+}
+
+impl Format {
+    /// The format of the config at `path`, going by its extension.
+    pub fn of(path: &Path) -> Self {
+        match path.extension() {
+            Some(ext) if ext == "toml" => Self::Toml,
+            _ => Self::Yaml,
+        }
+    }
+}
+
+/// How deep imports may be nested, as in alacritty.
+const IMPORT_DEPTH_LIMIT: usize = 5;
+
+/// A config about to be written. Sections that aren't set are left out, so
+/// that they don't override the configs it imports.
 #[derive(Debug, Default)]
 pub struct Config {
-    font: Font,
-    theme: Theme,
-    dimensions: Window,
+    /// Files imported by the config, as written in it.
+    imports: Vec<String>,
+    font: Option<Font>,
+    theme: Option<Theme>,
+    window: Option<Window>,
 }
 
-#[derive(Debug)]
+impl Config {
+    pub const fn theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
+
+    /// Import `path` after the existing imports, so that it takes precedence
+    /// over them, unless it's already imported.
+    pub fn add_import(&mut self, path: &Path, config_path: &Path) {
+        let imported = self
+            .imports
+            .iter()
+            .any(|import| resolve_import(import, config_path) == path);
+
+        if !imported {
+            self.imports.push(import_name(path));
+        }
+    }
+}
+
+#[derive(Debug, Default)]
 pub struct PartialConfig {
+    imports: Vec<String>,
     font: Option<Font>,
     theme: Option<Theme>,
-    dimensions: Option<Window>,
+    window: Option<Window>,
 }
 
 impl PartialConfig {
-    pub const fn new(font: Option<Font>, theme: Option<Theme>, dimensions: Option<Window>) -> Self {
+    pub const fn new(font: Option<Font>, theme: Option<Theme>, window: Option<Window>) -> Self {
         Self {
+            imports: Vec::new(),
             font,
             theme,
-            dimensions,
+            window,
         }
     }
 
@@ -30,17 +87,24 @@ impl PartialConfig {
         self.font.as_ref()
     }
 
-    // TODO clean this code up, specify default size in only one place
-    pub fn font_size_or_default(&self) -> u16 {
-        match &self.font {
-            Some(ref f) => f.size,
-            None => FontSize::default(),
-        }
-        .0
+    pub const fn theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
+
+    pub const fn window(&self) -> Option<&Window> {
+        self.window.as_ref()
+    }
+
+    pub fn font_size_or_default(&self) -> FontSize {
+        self.font
+            .as_ref()
+            .and_then(|font| font.size)
+            .unwrap_or_default()
     }
 }
 
-trait ToHex {
+/// Format a color as `#rrggbb`.
+pub trait ToHex {
     fn to_hex(self) -> String;
 }
 
@@ -50,13 +114,51 @@ impl ToHex for RGB8 {
     }
 }
 
-impl fmt::Display for Config {
+impl fmt::Display for CellColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Rgb(rgb) => write!(f, "'{}'", rgb.to_hex()),
+            Self::CellForeground => f.write_str("CellForeground"),
+            Self::CellBackground => f.write_str("CellBackground"),
+        }
+    }
+}
+
+const FG_BG: (&str, &str) = ("foreground", "background");
+
+/// Write one `colors` subsection holding a foreground/background pair, using
+/// alacritty's key names for that section. Nothing is written if neither
+/// color is set.
+fn write_color_pair(
+    f: &mut fmt::Formatter<'_>,
+    indent: usize,
+    name: &str,
+    (foreground_key, background_key): (&str, &str),
+    pair: Option<ColorPair>,
+) -> fmt::Result {
+    let pair = match pair {
+        Some(pair) if pair.foreground.is_some() || pair.background.is_some() => pair,
+        _ => return Ok(()),
+    };
+
+    write!(f, "\n{:indent$}{}:", "", name, indent = indent)?;
+    for (key, color) in [
+        (foreground_key, pair.foreground),
+        (background_key, pair.background),
+    ] {
+        if let Some(color) = color {
+            write!(f, "\n{:indent$}{}: {}", "", key, color, indent = indent + 4)?;
+        }
+    }
+
+    Ok(())
+}
+
+impl fmt::Display for Theme {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
             "\
-{}
-{}
 colors:
     primary:
         background: '{}'
@@ -79,86 +181,1246 @@ colors:
         magenta: '{}'
         cyan:    '{}'
         white:   '{}'",
-            self.font,
-            // terminal width/height
-            self.dimensions,
             // background/foreground
-            self.theme.background.to_hex(),
-            self.theme.foreground.to_hex(),
+            self.background.to_hex(),
+            self.foreground.to_hex(),
             // normal colors
-            self.theme.normal.black.to_hex(),
-            self.theme.normal.red.to_hex(),
-            self.theme.normal.green.to_hex(),
-            self.theme.normal.yellow.to_hex(),
-            self.theme.normal.blue.to_hex(),
-            self.theme.normal.magenta.to_hex(),
-            self.theme.normal.cyan.to_hex(),
-            self.theme.normal.white.to_hex(),
+            self.normal.black.to_hex(),
+            self.normal.red.to_hex(),
+            self.normal.green.to_hex(),
+            self.normal.yellow.to_hex(),
+            self.normal.blue.to_hex(),
+            self.normal.magenta.to_hex(),
+            self.normal.cyan.to_hex(),
+            self.normal.white.to_hex(),
             // bright colors
-            self.theme.bright.black.to_hex(),
-            self.theme.bright.red.to_hex(),
-            self.theme.bright.green.to_hex(),
-            self.theme.bright.yellow.to_hex(),
-            self.theme.bright.blue.to_hex(),
-            self.theme.bright.magenta.to_hex(),
-            self.theme.bright.cyan.to_hex(),
-            self.theme.bright.white.to_hex(),
-        )
+            self.bright.black.to_hex(),
+            self.bright.red.to_hex(),
+            self.bright.green.to_hex(),
+            self.bright.yellow.to_hex(),
+            self.bright.blue.to_hex(),
+            self.bright.magenta.to_hex(),
+            self.bright.cyan.to_hex(),
+            self.bright.white.to_hex(),
+        )?;
+
+        if let Some(dim) = self.dim {
+            write!(
+                f,
+                "
+    dim:
+        black:   '{}'
+        red:     '{}'
+        green:   '{}'
+        yellow:  '{}'
+        blue:    '{}'
+        magenta: '{}'
+        cyan:    '{}'
+        white:   '{}'",
+                dim.black.to_hex(),
+                dim.red.to_hex(),
+                dim.green.to_hex(),
+                dim.yellow.to_hex(),
+                dim.blue.to_hex(),
+                dim.magenta.to_hex(),
+                dim.cyan.to_hex(),
+                dim.white.to_hex(),
+            )?;
+        }
+        if !self.indexed_colors.is_empty() {
+            f.write_str("\n    indexed_colors:")?;
+            for indexed in &self.indexed_colors {
+                write!(
+                    f,
+                    "\n        - {{ index: {}, color: '{}' }}",
+                    indexed.index,
+                    indexed.color.to_hex()
+                )?;
+            }
+        }
+
+        write_color_pair(f, 4, "cursor", ("text", "cursor"), self.cursor)?;
+        write_color_pair(
+            f,
+            4,
+            "vi_mode_cursor",
+            ("text", "cursor"),
+            self.vi_mode_cursor,
+        )?;
+        write_color_pair(f, 4, "selection", ("text", "background"), self.selection)?;
+
+        if self.search_matches.is_some() || self.search_focused_match.is_some() {
+            f.write_str("\n    search:")?;
+            write_color_pair(f, 8, "matches", FG_BG, self.search_matches)?;
+            write_color_pair(f, 8, "focused_match", FG_BG, self.search_focused_match)?;
+        }
+        if self.hints_start.is_some() || self.hints_end.is_some() {
+            f.write_str("\n    hints:")?;
+            write_color_pair(f, 8, "start", FG_BG, self.hints_start)?;
+            write_color_pair(f, 8, "end", FG_BG, self.hints_end)?;
+        }
+
+        write_color_pair(f, 4, "footer_bar", FG_BG, self.footer_bar)?;
+        write_color_pair(f, 4, "line_indicator", FG_BG, self.line_indicator)
     }
 }
 
-// TODO return result type
+impl Config {
+    /// Everything but the `colors` section.
+    fn settings(&self) -> String {
+        let mut sections = Vec::new();
+
+        if !self.imports.is_empty() {
+            let mut imports = "import:".to_owned();
+            for import in &self.imports {
+                imports.push_str(&format!("\n    - {}", yaml_quote(import)));
+            }
+            sections.push(imports);
+        }
+        if let Some(ref font) = self.font {
+            sections.push(font.to_string());
+        }
+        if let Some(ref window) = self.window {
+            sections.push(window.to_string());
+        }
+
+        sections.join("\n")
+    }
+}
+
+/// Only the sections that are set are written.
+impl fmt::Display for PartialConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut sections = Vec::new();
+        if let Some(ref font) = self.font {
+            sections.push(font.to_string());
+        }
+        if let Some(ref window) = self.window {
+            sections.push(window.to_string());
+        }
+        if let Some(ref theme) = self.theme {
+            sections.push(theme.to_string());
+        }
+
+        f.write_str(&sections.join("\n"))
+    }
+}
+
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let settings = self.settings();
+        match self.theme {
+            Some(ref theme) if settings.is_empty() => write!(f, "{}", theme),
+            Some(ref theme) => write!(f, "{}\n{}", settings, theme),
+            None => f.write_str(&settings),
+        }
+    }
+}
+
+/// Write `config` in the given format, leaving out the `colors` section
+/// unless `colors` is set.
+pub fn render(config: &Config, format: Format, colors: bool) -> String {
+    if colors {
+        convert(config.to_string(), format)
+    } else {
+        convert(config.settings(), format)
+    }
+}
+
+/// Write only the `colors` section, for a config that is imported.
+pub fn render_theme(theme: &Theme, format: Format) -> String {
+    convert(theme.to_string(), format)
+}
+
+/// Convert a config generated as YAML to `format`.
+fn convert(yaml: String, format: Format) -> String {
+    match format {
+        Format::Yaml => yaml,
+        Format::Toml => {
+            // the YAML was generated above, so it's known to be valid
+            let doc = YamlLoader::load_from_str(&yaml)
+                .ok()
+                .and_then(|mut docs| docs.pop())
+                .unwrap_or(Yaml::Null);
+
+            let toml = match yaml_to_toml(doc) {
+                Some(toml::Value::Table(table)) => toml::to_string(&table).unwrap_or_default(),
+                _ => String::new(),
+            };
+
+            // the serializer leaves out the blank line before a table whose
+            // parent only holds tables
+            let mut spaced = String::with_capacity(toml.len());
+            for line in toml.lines() {
+                if line.starts_with('[') && !spaced.is_empty() && !spaced.ends_with("\n\n") {
+                    spaced.push('\n');
+                }
+                spaced.push_str(line);
+                spaced.push('\n');
+            }
+            spaced.truncate(spaced.trim_end().len());
+            spaced
+        }
+    }
+}
 
 /// Parse current alacritty config into a Config struct that we can manipulate.
 /// This function WILL ignore all aspects of the config that are not currently
-/// implemented.
-pub fn parse(source: &str) -> Option<PartialConfig> {
-    let parsed = YamlLoader::load_from_str(source).ok()?;
-    let parsed = parsed.get(0)?;
-
-    Some(PartialConfig {
-        font: Some(Font::new(
-            parsed["font"]["normal"]["family"].as_str(),
-            parsed["font"]["size"]
-                .as_i64()
-                // TODO handle error
-                .map(|x| FontSize(x as u16))
-                .unwrap_or_default()
-                .0,
-        )),
-        // TODO actually parse the theme
-        theme: None,
-        dimensions: Some(Window::new_or_default(
-            parsed["window"]["dimensions"]["columns"]
-                .as_i64()
-                .map(|x| x as u16),
-            parsed["window"]["dimensions"]["lines"]
-                .as_i64()
-                // TODO add error handling if it doesn't fit in a u16
-                .map(|x| x as u16),
-        )),
+/// implemented. Imports are not followed, see `parse_file` for that.
+pub fn parse(source: &str, format: Format) -> Result<PartialConfig> {
+    from_document(&Document::load(source, format)?)
+}
+
+/// Parse the config at `path`, whose contents are `source`, along with the
+/// configs it imports. As in alacritty, later imports take precedence over
+/// earlier ones, and the config itself over all of its imports.
+pub fn parse_file(path: &Path, source: &str) -> Result<PartialConfig> {
+    from_document(&Document::load_file(path, source, &mut Vec::new())?)
+}
+
+/// Parse the config at `path` both as written and with its imports merged
+/// in, as `parse_file` does. Changes are made to the former, so that values
+/// of the imports aren't copied into the config, and the latter is what
+/// alacritty currently uses.
+pub fn parse_own_and_merged(path: &Path, source: &str) -> Result<(PartialConfig, PartialConfig)> {
+    let doc = Document::load(source, Format::of(path)).map_err(|e| e.in_file(path))?;
+    let own = from_document(&doc)?;
+    let merged = from_document(&doc.with_imports(path, &mut Vec::new())?)?;
+
+    Ok((own, merged))
+}
+
+/// Load the theme in the `colors` section of the config at `path`. Theme
+/// collections such as alacritty-theme ship their themes as such configs.
+pub fn load_theme(path: &Path) -> Result<Theme> {
+    let source = fs::read_to_string(path).map_err(|e| Error::from(e).in_file(path))?;
+
+    parse_file(path, &source)
+        .map_err(|e| e.in_file(path))?
+        .theme
+        .ok_or_else(|| Error::new(ErrorKind::NoTheme).in_file(path))
+}
+
+fn from_document(doc: &Document) -> Result<PartialConfig> {
+    if doc.root.is_null() {
+        return Ok(PartialConfig::default());
+    }
+
+    let family = doc.str("font.normal.family")?;
+    let font_size = match doc.f32("font.size")? {
+        Some(size) => Some(FontSize::new(size).map_err(|e| doc.error("font.size", e))?),
+        None => None,
+    };
+    let font = match (family, font_size) {
+        (None, None) => None,
+        (family, size) => Some(Font::new(family, size)),
+    };
+    let window = parse_window(doc)?;
+
+    Ok(PartialConfig {
+        imports: doc.imports()?,
+        font,
+        theme: parse_theme(doc)?,
+        window: if window.is_empty() {
+            None
+        } else {
+            Some(window)
+        },
+    })
+}
+
+/// Path of an import of the config at `config_path`. `~` is expanded, and
+/// relative paths are relative to the importing config.
+pub fn resolve_import(import: &str, config_path: &Path) -> PathBuf {
+    let home = dirs::home_dir();
+
+    let path = match (import.strip_prefix('~'), home) {
+        (Some(""), Some(home)) => home,
+        (Some(rest), Some(home)) if rest.starts_with('/') => home.join(&rest[1..]),
+        _ => PathBuf::from(import),
+    };
+
+    match config_path.parent() {
+        Some(dir) if path.is_relative() => dir.join(path),
+        _ => path,
+    }
+}
+
+/// How to write `path` in an import, using `~` for the home directory.
+fn import_name(path: &Path) -> String {
+    match dirs::home_dir().and_then(|home| path.strip_prefix(home).ok().map(Path::to_owned)) {
+        Some(rest) => format!("~/{}", rest.display()),
+        None => path.display().to_string(),
+    }
+}
+
+/// Merge `other` into `base`, as alacritty merges imports: mappings are
+/// merged key by key, sequences are concatenated, nulls are ignored and
+/// everything else is replaced. The dotted path of every sequence appended
+/// to is added to `appended`, along with its length before.
+fn merge_yaml(base: &mut Yaml, other: Yaml, path: &str, appended: &mut Vec<(String, usize)>) {
+    match (base, other) {
+        (_, Yaml::Null) | (_, Yaml::BadValue) => {}
+        (Yaml::Hash(base), Yaml::Hash(other)) => {
+            for (key, value) in other {
+                match base.get_mut(&key) {
+                    Some(base_value) => {
+                        let path = match key.as_str() {
+                            Some(key) if path.is_empty() => key.to_owned(),
+                            Some(key) => format!("{}.{}", path, key),
+                            // such keys can't be looked up anyway
+                            None => String::new(),
+                        };
+                        merge_yaml(base_value, value, &path, appended);
+                    }
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Yaml::Array(base), Yaml::Array(other)) => {
+            appended.push((path.to_owned(), base.len()));
+            base.extend(other);
+        }
+        (base, other) => *base = other,
+    }
+}
+
+/// Integer types that config values can be read into.
+trait Int: TryFrom<i64> + fmt::Display {
+    const MIN: Self;
+    const MAX: Self;
+}
+
+macro_rules! impl_int {
+    ($($t:ty),*) => {
+        $(impl Int for $t {
+            const MIN: Self = <$t>::MIN;
+            const MAX: Self = <$t>::MAX;
+        })*
+    };
+}
+
+impl_int!(u8, u16, i32);
+
+/// Where a value was found.
+#[derive(Debug)]
+struct Location {
+    /// The imported config the value is from, or `None` for the config being
+    /// parsed.
+    file: Option<PathBuf>,
+    line: usize,
+}
+
+/// A parsed YAML document, along with the line each value was found on so
+/// that errors can point at it.
+#[derive(Debug)]
+struct Document {
+    root: Yaml,
+    lines: HashMap<String, Location>,
+}
+
+impl Document {
+    fn load(source: &str, format: Format) -> Result<Self> {
+        let (root, lines) = match format {
+            Format::Yaml => {
+                let mut loader = Loader::default();
+                Parser::new(source.chars()).load(&mut loader, false)?;
+                (loader.root.unwrap_or(Yaml::Null), loader.lines)
+            }
+            // TOML is read through the same YAML representation so that both
+            // formats share the code interpreting their values
+            Format::Toml => (toml_to_yaml(source.parse()?), toml_lines(source)),
+        };
+
+        Ok(Self {
+            root,
+            lines: lines
+                .into_iter()
+                .map(|(key, line)| (key, Location { file: None, line }))
+                .collect(),
+        })
+    }
+
+    /// Load the config at `path` and merge in everything it imports. `stack`
+    /// holds the configs importing this one, to catch import cycles.
+    fn load_file(path: &Path, source: &str, stack: &mut Vec<PathBuf>) -> Result<Self> {
+        let mut doc = Self::load(source, Format::of(path)).map_err(|e| e.in_file(path))?;
+        // only errors in imported files need to name their file, the config
+        // being parsed is named by the caller
+        if !stack.is_empty() {
+            for location in doc.lines.values_mut() {
+                location.file = Some(path.to_owned());
+            }
+        }
+
+        doc.with_imports(path, stack)
+    }
+
+    /// Merge everything the config at `path`, which this document was loaded
+    /// from, imports into it.
+    fn with_imports(self, path: &Path, stack: &mut Vec<PathBuf>) -> Result<Self> {
+        let imports = self.imports().map_err(|e| e.in_file(path))?;
+        if imports.is_empty() {
+            return Ok(self);
+        }
+        if stack.len() >= IMPORT_DEPTH_LIMIT {
+            return Err(self.error("import", ErrorKind::ImportDepth(IMPORT_DEPTH_LIMIT)));
+        }
+
+        stack.push(fs::canonicalize(path).unwrap_or_else(|_| path.to_owned()));
+
+        let mut merged = Self {
+            root: Yaml::Null,
+            lines: HashMap::new(),
+        };
+        for import in &imports {
+            let import_path = resolve_import(import, path);
+
+            let canonical = fs::canonicalize(&import_path).unwrap_or_else(|_| import_path.clone());
+            if stack.contains(&canonical) {
+                return Err(self
+                    .error("import", ErrorKind::ImportCycle(import_path))
+                    .in_file(path));
+            }
+
+            // alacritty skips imports that don't exist
+            let source = match fs::read_to_string(&import_path) {
+                Ok(source) => source,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(Error::from(e).in_file(&import_path)),
+            };
+
+            merged.merge(Self::load_file(&import_path, &source, stack)?);
+        }
+
+        stack.pop();
+
+        merged.merge(self);
+        Ok(merged)
+    }
+
+    /// Merge `other` into this document, with `other` taking precedence.
+    fn merge(&mut self, other: Self) {
+        let mut appended = Vec::new();
+        merge_yaml(&mut self.root, other.root, "", &mut appended);
+
+        // elements appended to a sequence moved past those already in it
+        let lines = other.lines.into_iter().map(|(key, location)| {
+            for (path, offset) in &appended {
+                let rest = key
+                    .strip_prefix(path.as_str())
+                    .and_then(|rest| rest.strip_prefix('.'));
+                if let Some(rest) = rest {
+                    let (index, tail) = rest.split_once('.').unwrap_or((rest, ""));
+                    if let Ok(index) = index.parse::<usize>() {
+                        let key = format!("{}.{}", path, index + offset);
+                        return match tail {
+                            "" => (key, location),
+                            tail => (format!("{}.{}", key, tail), location),
+                        };
+                    }
+                }
+            }
+            (key, location)
+        });
+        self.lines.extend(lines);
+    }
+
+    /// The configs imported by this one, as written in it. alacritty 0.14
+    /// moved `import` into the `general` section.
+    fn imports(&self) -> Result<Vec<String>> {
+        let key = match self.get("general.import") {
+            Yaml::BadValue | Yaml::Null => "import",
+            _ => "general.import",
+        };
+
+        match self.get(key) {
+            Yaml::BadValue | Yaml::Null => Ok(Vec::new()),
+            Yaml::Array(imports) => imports
+                .iter()
+                .enumerate()
+                .map(|(i, import)| match import {
+                    Yaml::String(import) => Ok(import.clone()),
+                    _ => Err(self.invalid(&format!("{}.{}", key, i), "a path")),
+                })
+                .collect(),
+            _ => Err(self.invalid(key, "a list of paths")),
+        }
+    }
+
+    /// Look up a dotted key such as `colors.normal.red`. Sequence elements are
+    /// addressed by their index, e.g. `colors.indexed_colors.0.color`.
+    fn get(&self, key: &str) -> &Yaml {
+        key.split('.')
+            .fold(&self.root, |yaml, k| match k.parse::<usize>() {
+                Ok(i) if yaml.as_vec().is_some() => &yaml[i],
+                _ => &yaml[k],
+            })
+    }
+
+    /// Attach the line of `key` to an error, or else that of the closest
+    /// parent key that has one, e.g. for values in a TOML inline table.
+    fn error<E: Into<Error>>(&self, key: &str, e: E) -> Error {
+        let e = e.into();
+
+        let mut key = key;
+        loop {
+            if let Some(location) = self.lines.get(key) {
+                let e = e.at_line(location.line);
+                return match location.file {
+                    Some(ref file) => e.in_file(file),
+                    None => e,
+                };
+            }
+            match key.rsplit_once('.') {
+                Some((parent, _)) => key = parent,
+                None => return e,
+            }
+        }
+    }
+
+    fn invalid(&self, key: &str, expected: &'static str) -> Error {
+        self.error(
+            key,
+            ErrorKind::InvalidValue {
+                key: key.to_owned(),
+                expected,
+            },
+        )
+    }
+
+    fn str(&self, key: &str) -> Result<Option<&str>> {
+        match self.get(key) {
+            Yaml::BadValue | Yaml::Null => Ok(None),
+            Yaml::String(s) => Ok(Some(s)),
+            _ => Err(self.invalid(key, "a string")),
+        }
+    }
+
+    fn bool(&self, key: &str) -> Result<Option<bool>> {
+        match self.get(key) {
+            Yaml::BadValue | Yaml::Null => Ok(None),
+            Yaml::Boolean(b) => Ok(Some(*b)),
+            _ => Err(self.invalid(key, "true or false")),
+        }
+    }
+
+    fn f32(&self, key: &str) -> Result<Option<f32>> {
+        let yaml = self.get(key);
+
+        match yaml {
+            Yaml::BadValue | Yaml::Null => Ok(None),
+            Yaml::Integer(i) => Ok(Some(*i as f32)),
+            Yaml::Real(_) => Ok(yaml.as_f64().map(|x| x as f32)),
+            _ => Err(self.invalid(key, "a number")),
+        }
+    }
+
+    fn int<T: Int>(&self, key: &str) -> Result<Option<T>> {
+        match self.get(key) {
+            Yaml::BadValue | Yaml::Null => Ok(None),
+            Yaml::Integer(i) => T::try_from(*i)
+                .map(Some)
+                .map_err(|_| self.error(key, Error::out_of_range(key, i, T::MIN, T::MAX))),
+            _ => Err(self.invalid(key, "an integer")),
+        }
+    }
+
+    /// A color as written. YAML reads an unquoted `0xrrggbb` as a number,
+    /// which can't be told apart from an xterm color index once read, so
+    /// numbers are always taken as `0xrrggbb`; indices have to be quoted.
+    fn color_str(&self, key: &str) -> Result<Option<String>> {
+        match self.get(key) {
+            Yaml::BadValue | Yaml::Null => Ok(None),
+            Yaml::String(s) => Ok(Some(s.clone())),
+            Yaml::Integer(i @ 0..=0xff_ffff) => Ok(Some(format!("0x{:06x}", i))),
+            _ => Err(self.invalid(key, "a color")),
+        }
+    }
+
+    fn rgb(&self, key: &str) -> Result<Option<RGB8>> {
+        match self.color_str(key)? {
+            Some(color) => match parse_color(&color) {
+                Ok(rgb) => Ok(Some(rgb)),
+                Err(_) => Err(self.invalid_color(key, &color)),
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn cell_color(&self, key: &str) -> Result<Option<CellColor>> {
+        match self.color_str(key)?.as_deref() {
+            Some("CellForeground") => Ok(Some(CellColor::CellForeground)),
+            Some("CellBackground") => Ok(Some(CellColor::CellBackground)),
+            // alacritty uses `None` to unset a color
+            Some("None") | None => Ok(None),
+            Some(color) => match parse_color(color) {
+                Ok(rgb) => Ok(Some(CellColor::Rgb(rgb))),
+                Err(_) => Err(self.invalid_color(key, color)),
+            },
+        }
+    }
+
+    fn invalid_color(&self, key: &str, value: &str) -> Error {
+        self.error(
+            key,
+            ErrorKind::InvalidColor {
+                key: key.to_owned(),
+                value: value.to_owned(),
+            },
+        )
+    }
+}
+
+/// Path segment of the value currently being parsed.
+enum Segment {
+    /// Mapping key, or `None` if the next scalar is a key.
+    Key(Option<String>),
+    Index(usize),
+}
+
+/// Builds a YAML document the way `YamlLoader` does, recording the line
+/// every value starts on, keyed by its dotted path, in the same pass.
+#[derive(Default)]
+struct Loader {
+    /// The first document; any others are ignored, as by alacritty.
+    root: Option<Yaml>,
+    /// Containers being built, with their anchor.
+    nodes: Vec<(Yaml, usize)>,
+    /// Key of the value being built in each mapping, or `BadValue` if the
+    /// next node is a key.
+    keys: Vec<Yaml>,
+    anchors: HashMap<usize, Yaml>,
+    stack: Vec<Segment>,
+    lines: HashMap<String, usize>,
+}
+
+impl Loader {
+    fn record(&mut self, mark: Marker) {
+        let path = self
+            .stack
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Key(key) => key.clone(),
+                Segment::Index(i) => Some(i.to_string()),
+            })
+            .collect::<Vec<_>>()
+            .join(".");
+
+        // keep the line of the key rather than the value if both are known
+        self.lines.entry(path).or_insert_with(|| mark.line());
+    }
+
+    /// Move on to the next key or element of the innermost container.
+    fn advance(&mut self) {
+        match self.stack.last_mut() {
+            Some(Segment::Key(key)) => *key = None,
+            Some(Segment::Index(i)) => *i += 1,
+            None => {}
+        }
+    }
+
+    /// Add a finished node to the container being built.
+    fn insert(&mut self, (node, anchor): (Yaml, usize)) {
+        if anchor > 0 {
+            self.anchors.insert(anchor, node.clone());
+        }
+
+        match self.nodes.last_mut() {
+            None => {
+                self.root.get_or_insert(node);
+            }
+            Some((Yaml::Array(array), _)) => array.push(node),
+            Some((Yaml::Hash(hash), _)) => {
+                let key = self.keys.last_mut().expect("a mapping has a key");
+                if *key == Yaml::BadValue {
+                    *key = node;
+                } else {
+                    hash.insert(std::mem::replace(key, Yaml::BadValue), node);
+                }
+            }
+            Some(_) => unreachable!("only arrays and hashes are containers"),
+        }
+    }
+}
+
+/// The value of a scalar, read as by `YamlLoader`.
+fn scalar(value: String, style: TScalarStyle, tag: Option<TokenType>) -> Yaml {
+    if style != TScalarStyle::Plain {
+        return Yaml::String(value);
+    }
+
+    match tag {
+        Some(TokenType::Tag(handle, suffix)) if handle == "!!" => match suffix.as_str() {
+            "bool" => value.parse().map_or(Yaml::BadValue, Yaml::Boolean),
+            "int" => value.parse().map_or(Yaml::BadValue, Yaml::Integer),
+            "float" => match value.parse::<f64>() {
+                Ok(_) => Yaml::Real(value),
+                Err(_) => Yaml::BadValue,
+            },
+            "null" => match value.as_str() {
+                "~" | "null" => Yaml::Null,
+                _ => Yaml::BadValue,
+            },
+            _ => Yaml::String(value),
+        },
+        Some(_) => Yaml::String(value),
+        None => Yaml::from_str(&value),
+    }
+}
+
+impl MarkedEventReceiver for Loader {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        match ev {
+            Event::MappingStart(anchor) => {
+                self.record(mark);
+                self.stack.push(Segment::Key(None));
+                self.nodes.push((Yaml::Hash(Hash::new()), anchor));
+                self.keys.push(Yaml::BadValue);
+            }
+            Event::SequenceStart(anchor) => {
+                self.record(mark);
+                self.stack.push(Segment::Index(0));
+                self.nodes.push((Yaml::Array(Vec::new()), anchor));
+            }
+            Event::MappingEnd | Event::SequenceEnd => {
+                if let Event::MappingEnd = ev {
+                    self.keys.pop();
+                }
+                let node = self.nodes.pop().expect("a container was started");
+                self.insert(node);
+
+                self.stack.pop();
+                self.advance();
+            }
+            Event::Scalar(value, style, anchor, tag) => {
+                match self.stack.last_mut() {
+                    Some(Segment::Key(key @ None)) => {
+                        *key = Some(value.clone());
+                        self.record(mark);
+                    }
+                    _ => {
+                        self.record(mark);
+                        self.advance();
+                    }
+                }
+                self.insert((scalar(value, style, tag), anchor));
+            }
+            Event::Alias(id) => {
+                self.record(mark);
+                self.advance();
+                let node = self.anchors.get(&id).cloned().unwrap_or(Yaml::BadValue);
+                self.insert((node, 0));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn toml_to_yaml(value: toml::Value) -> Yaml {
+    match value {
+        toml::Value::String(s) => Yaml::String(s),
+        toml::Value::Integer(i) => Yaml::Integer(i),
+        toml::Value::Float(x) => Yaml::Real(format!("{:?}", x)),
+        toml::Value::Boolean(b) => Yaml::Boolean(b),
+        toml::Value::Datetime(date) => Yaml::String(date.to_string()),
+        toml::Value::Array(array) => Yaml::Array(array.into_iter().map(toml_to_yaml).collect()),
+        toml::Value::Table(table) => Yaml::Hash(
+            table
+                .into_iter()
+                .map(|(key, value)| (Yaml::String(key), toml_to_yaml(value)))
+                .collect::<Hash>(),
+        ),
+    }
+}
+
+/// Convert YAML to TOML. Returns `None` for values TOML can't hold, i.e.
+/// nulls, which are left out of tables and arrays.
+fn yaml_to_toml(yaml: Yaml) -> Option<toml::Value> {
+    Some(match yaml {
+        Yaml::String(s) => toml::Value::String(s),
+        Yaml::Integer(i) => toml::Value::Integer(i),
+        Yaml::Real(ref x) => toml::Value::Float(yaml.as_f64().unwrap_or_else(|| {
+            // as_f64 only fails for strings that aren't valid floats
+            x.parse().unwrap_or_default()
+        })),
+        Yaml::Boolean(b) => toml::Value::Boolean(b),
+        Yaml::Array(array) => {
+            toml::Value::Array(array.into_iter().filter_map(yaml_to_toml).collect())
+        }
+        Yaml::Hash(hash) => toml::Value::Table(
+            hash.into_iter()
+                .filter_map(|(key, value)| Some((key.into_string()?, yaml_to_toml(value)?)))
+                .collect(),
+        ),
+        Yaml::Alias(_) | Yaml::Null | Yaml::BadValue => return None,
+    })
+}
+
+/// Records the line every key of a TOML document is on, keyed by its dotted
+/// path, as `Loader` does for YAML. This only understands the subset of
+/// TOML alacritty configs are written in: one key or table header per line,
+/// with bare or quoted keys.
+fn toml_lines(source: &str) -> HashMap<String, usize> {
+    let mut lines = HashMap::new();
+    // number of elements of each array of tables seen so far
+    let mut array_lengths: HashMap<String, usize> = HashMap::new();
+    let mut table = String::new();
+
+    let key_path = |key: &str| {
+        key.split('.')
+            .map(|segment| segment.trim().trim_matches(|c| c == '"' || c == '\''))
+            .collect::<Vec<_>>()
+            .join(".")
+    };
+
+    for (i, line) in source.lines().enumerate() {
+        let line = line.trim();
+        let number = i + 1;
+
+        if line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(header) = line
+            .strip_prefix("[[")
+            .and_then(|rest| rest.split_once("]]"))
+        {
+            let path = key_path(header.0);
+            let length = array_lengths.entry(path.clone()).or_insert(0);
+            table = format!("{}.{}", path, length);
+            *length += 1;
+
+            lines.entry(path).or_insert(number);
+            lines.insert(table.clone(), number);
+        } else if let Some(header) = line.strip_prefix('[').and_then(|rest| rest.split_once(']')) {
+            table = key_path(header.0);
+            lines.insert(table.clone(), number);
+        } else if let Some((key, _)) = line.split_once('=') {
+            let key = key_path(key);
+            let path = if table.is_empty() {
+                key
+            } else {
+                format!("{}.{}", table, key)
+            };
+            lines.insert(path, number);
+        }
+    }
+
+    lines
+}
+
+fn parse_color_pair(
+    doc: &Document,
+    section: &str,
+    (foreground_key, background_key): (&str, &str),
+) -> Result<Option<ColorPair>> {
+    let pair = ColorPair {
+        foreground: doc.cell_color(&format!("{}.{}", section, foreground_key))?,
+        background: doc.cell_color(&format!("{}.{}", section, background_key))?,
+    };
+
+    Ok(if pair.foreground.is_some() || pair.background.is_some() {
+        Some(pair)
+    } else {
+        None
+    })
+}
+
+fn parse_theme_colors(doc: &Document, section: &str) -> Result<Option<ThemeColors>> {
+    let color = |name: &str| doc.rgb(&format!("{}.{}", section, name));
+
+    Ok(
+        match (
+            color("black")?,
+            color("red")?,
+            color("green")?,
+            color("yellow")?,
+            color("blue")?,
+            color("magenta")?,
+            color("cyan")?,
+            color("white")?,
+        ) {
+            (
+                Some(black),
+                Some(red),
+                Some(green),
+                Some(yellow),
+                Some(blue),
+                Some(magenta),
+                Some(cyan),
+                Some(white),
+            ) => Some(ThemeColors {
+                black,
+                red,
+                green,
+                yellow,
+                blue,
+                magenta,
+                cyan,
+                white,
+            }),
+            _ => None,
+        },
+    )
+}
+
+/// Parse the `colors` section. Returns `None` unless at least the primary,
+/// normal and bright colors are all present.
+fn parse_theme(doc: &Document) -> Result<Option<Theme>> {
+    let (background, foreground) = match (
+        doc.rgb("colors.primary.background")?,
+        doc.rgb("colors.primary.foreground")?,
+    ) {
+        (Some(background), Some(foreground)) => (background, foreground),
+        _ => return Ok(None),
+    };
+    let (normal, bright) = match (
+        parse_theme_colors(doc, "colors.normal")?,
+        parse_theme_colors(doc, "colors.bright")?,
+    ) {
+        (Some(normal), Some(bright)) => (normal, bright),
+        _ => return Ok(None),
+    };
+
+    let mut indexed_colors = Vec::new();
+    let count = doc
+        .get("colors.indexed_colors")
+        .as_vec()
+        .map_or(0, Vec::len);
+    for i in 0..count {
+        let index_key = format!("colors.indexed_colors.{}.index", i);
+        let color_key = format!("colors.indexed_colors.{}.color", i);
+
+        if let (Some(index), Some(color)) = (doc.int::<u8>(&index_key)?, doc.rgb(&color_key)?) {
+            indexed_colors.push(IndexedColor::new(index, color).ok_or_else(|| {
+                doc.error(&index_key, Error::out_of_range(&index_key, index, 16, 255))
+            })?);
+        }
+    }
+
+    Ok(Some(Theme {
+        background,
+        foreground,
+        normal,
+        bright,
+        dim: parse_theme_colors(doc, "colors.dim")?,
+        indexed_colors,
+        cursor: parse_color_pair(doc, "colors.cursor", ("text", "cursor"))?,
+        vi_mode_cursor: parse_color_pair(doc, "colors.vi_mode_cursor", ("text", "cursor"))?,
+        selection: parse_color_pair(doc, "colors.selection", ("text", "background"))?,
+        search_matches: parse_color_pair(doc, "colors.search.matches", FG_BG)?,
+        search_focused_match: parse_color_pair(doc, "colors.search.focused_match", FG_BG)?,
+        hints_start: parse_color_pair(doc, "colors.hints.start", FG_BG)?,
+        hints_end: parse_color_pair(doc, "colors.hints.end", FG_BG)?,
+        footer_bar: parse_color_pair(doc, "colors.footer_bar", FG_BG)?,
+        line_indicator: parse_color_pair(doc, "colors.line_indicator", FG_BG)?,
+    }))
+}
+
+fn parse_window(doc: &Document) -> Result<Window> {
+    // `background_opacity` is the pre-0.10 name of `window.opacity`
+    let (opacity_key, opacity) = match doc.f32("window.opacity")? {
+        Some(opacity) => ("window.opacity", Some(opacity)),
+        None => ("background_opacity", doc.f32("background_opacity")?),
+    };
+
+    Ok(Window {
+        dimensions: doc
+            .int("window.dimensions.columns")?
+            .zip(doc.int("window.dimensions.lines")?)
+            .map(|(columns, lines)| Dimensions::new(columns, lines)),
+        position: doc
+            .int("window.position.x")?
+            .zip(doc.int("window.position.y")?)
+            .map(|(x, y)| Position { x, y }),
+        padding: doc
+            .int("window.padding.x")?
+            .zip(doc.int("window.padding.y")?)
+            .map(|(x, y)| Padding { x, y }),
+        dynamic_padding: doc.bool("window.dynamic_padding")?,
+        decorations: doc
+            .str("window.decorations")?
+            .map(|d| {
+                d.parse::<Decorations>().map_err(|_| {
+                    doc.invalid(
+                        "window.decorations",
+                        "full, none, transparent or buttonless",
+                    )
+                })
+            })
+            .transpose()?,
+        opacity: opacity
+            .map(|o| {
+                Opacity::new(o)
+                    .map_err(|_| doc.error(opacity_key, Error::out_of_range(opacity_key, o, 0, 1)))
+            })
+            .transpose()?,
+        startup_mode: doc
+            .str("window.startup_mode")?
+            .map(|m| {
+                m.parse::<StartupMode>().map_err(|_| {
+                    doc.invalid(
+                        "window.startup_mode",
+                        "Windowed, Maximized, Fullscreen or SimpleFullscreen",
+                    )
+                })
+            })
+            .transpose()?,
+        title: doc.str("window.title")?.map(str::to_owned),
+        dynamic_title: doc.bool("window.dynamic_title")?,
+        class: match doc.get("window.class") {
+            Yaml::String(class) => Some(Class {
+                instance: class.to_owned(),
+                general: class.to_owned(),
+            }),
+            _ => doc
+                .str("window.class.instance")?
+                .zip(doc.str("window.class.general")?)
+                .map(|(instance, general)| Class {
+                    instance: instance.to_owned(),
+                    general: general.to_owned(),
+                }),
+        },
     })
 }
 
+/// The config with the sections of `new_config` written over those of
+/// `old_config`. Sections neither sets are left out.
 pub fn merge(new_config: PartialConfig, old_config: PartialConfig) -> Config {
     Config {
-        // using match instead of .unwrap_or_else() because all unwrap methods
-        // take ownership of the variable and thus you can't use captures in
-        // subsequent unwrap calls because they have already been moved into
-        // the closure
-        font: match new_config.font {
-            Some(font) => font,
-            None => old_config.font.unwrap_or_default(),
+        imports: if new_config.imports.is_empty() {
+            old_config.imports
+        } else {
+            new_config.imports
         },
-        // TODO isn't the logic set up so that it's literally impossible
-        // for args.theme to not be present?
-        theme: match new_config.theme {
-            Some(theme) => theme,
-            None => old_config.theme.unwrap_or_default(),
+        font: match (new_config.font, old_config.font) {
+            (Some(font), Some(old)) => Some(Font {
+                family: font.family.or(old.family),
+                size: font.size.or(old.size),
+            }),
+            (font, old) => font.or(old),
         },
-        dimensions: match new_config.dimensions {
-            Some(dimensions) => dimensions,
-            None => old_config.dimensions.unwrap_or_default(),
+        theme: new_config.theme.or(old_config.theme),
+        window: match (new_config.window, old_config.window) {
+            (Some(window), old) => Some(window.merge(old.unwrap_or_default())),
+            (None, old) => old,
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    /// An empty directory for the configs of the test `name`. It's emptied
+    /// again by the next run rather than removed, so that it can be looked at
+    /// when the test fails.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("alacritty-conf-test-{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write(dir: &Path, name: &str, contents: &str) -> PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn load(path: &Path) -> Result<Document> {
+        let source = fs::read_to_string(path).unwrap();
+        Document::load_file(path, &source, &mut Vec::new())
+    }
+
+    #[test]
+    fn import_precedence() {
+        let dir = test_dir("import_precedence");
+        write(
+            &dir,
+            "a.yml",
+            "font:\n  size: 10\nwindow:\n  opacity: 0.5\n",
+        );
+        write(
+            &dir,
+            "b.yml",
+            "font:\n  size: 12\n  normal:\n    family: B\n",
+        );
+        let main = write(
+            &dir,
+            "main.yml",
+            "import:\n  - a.yml\n  - b.yml\nfont:\n  normal:\n    family: Main\n",
+        );
+
+        let doc = load(&main).unwrap();
+        // later imports win over earlier ones
+        assert_eq!(doc.get("font.size").as_i64(), Some(12));
+        // and the config itself over its imports
+        assert_eq!(doc.get("font.normal.family").as_str(), Some("Main"));
+        // values set only once are kept, wherever they're from
+        assert_eq!(doc.get("window.opacity").as_f64(), Some(0.5));
+    }
+
+    #[test]
+    fn imported_sequences_are_concatenated() {
+        let dir = test_dir("imported_sequences_are_concatenated");
+        write(
+            &dir,
+            "a.yml",
+            "colors:\n  indexed_colors:\n    - { index: 16, color: '#000000' }\n",
+        );
+        let main = write(
+            &dir,
+            "main.yml",
+            "import: [a.yml]\ncolors:\n  indexed_colors:\n    - { index: 17, color: '#ffffff' }\n",
+        );
+
+        let doc = load(&main).unwrap();
+        let indices: Vec<_> = doc
+            .get("colors.indexed_colors")
+            .as_vec()
+            .unwrap()
+            .iter()
+            .map(|indexed| indexed["index"].as_i64())
+            .collect();
+        assert_eq!(indices, [Some(16), Some(17)]);
+        // the config's own element is reported on its own line
+        assert_eq!(doc.lines["colors.indexed_colors.1"].line, 4);
+    }
+
+    #[test]
+    fn missing_imports_are_skipped() {
+        let dir = test_dir("missing_imports_are_skipped");
+        write(&dir, "b.yml", "font:\n  size: 12\n");
+        let main = write(&dir, "main.yml", "import:\n  - missing.yml\n  - b.yml\n");
+
+        assert_eq!(load(&main).unwrap().get("font.size").as_i64(), Some(12));
+    }
+
+    #[test]
+    fn import_cycle() {
+        let dir = test_dir("import_cycle");
+        fs::create_dir(dir.join("sub")).unwrap();
+        // the cycle is only found by comparing canonical paths
+        write(&dir, "a.yml", "import:\n  - sub/../main.yml\n");
+        let main = write(&dir, "main.yml", "import:\n  - ./a.yml\n");
+
+        let e = load(&main).unwrap_err();
+        assert!(e.to_string().contains("import cycle"), "{}", e);
+
+        let own = write(&dir, "self.yml", "import:\n  - self.yml\n");
+        let e = load(&own).unwrap_err();
+        assert!(e.to_string().contains("import cycle"), "{}", e);
+    }
+
+    #[test]
+    fn import_depth_limit() {
+        let dir = test_dir("import_depth_limit");
+        // main.yml imports 1.yml, which imports 2.yml and so on
+        let chain = |depth: usize| {
+            for i in 0..depth {
+                let name = if i == 0 {
+                    "main.yml".to_owned()
+                } else {
+                    format!("{}.yml", i)
+                };
+                write(&dir, &name, &format!("import:\n  - {}.yml\n", i + 1));
+            }
+            write(&dir, &format!("{}.yml", depth), "font:\n  size: 12\n");
+            dir.join("main.yml")
+        };
+
+        let doc = load(&chain(IMPORT_DEPTH_LIMIT)).unwrap();
+        assert_eq!(doc.get("font.size").as_i64(), Some(12));
+
+        let e = load(&chain(IMPORT_DEPTH_LIMIT + 1)).unwrap_err();
+        assert!(e.to_string().contains("nested more than 5 deep"), "{}", e);
+    }
+
+    #[test]
+    fn imports_are_resolved() {
+        let config = Path::new("/etc/alacritty/alacritty.yml");
+        let home = dirs::home_dir().unwrap();
+
+        assert_eq!(resolve_import("~", config), home);
+        assert_eq!(
+            resolve_import("~/themes/a.yml", config),
+            home.join("themes/a.yml")
+        );
+        assert_eq!(
+            resolve_import("themes/a.yml", config),
+            Path::new("/etc/alacritty/themes/a.yml")
+        );
+        assert_eq!(resolve_import("/a.yml", config), Path::new("/a.yml"));
+        // only a leading `~/` is the home directory
+        assert_eq!(
+            resolve_import("~user/a.yml", config),
+            Path::new("/etc/alacritty/~user/a.yml")
+        );
+    }
+
+    #[test]
+    fn unquoted_hex_colors() {
+        let palette = "
+        black: '#000000'
+        red: '208'
+        green: '#00ff00'
+        yellow: '#ffff00'
+        blue: 0x0000ff
+        magenta: '#ff00ff'
+        cyan: '#00ffff'
+        white: 0xffffff";
+        let source = format!(
+            "colors:
+    primary:
+        background: 0x000000
+        foreground: '#ffffff'
+    normal:{}
+    bright:{}",
+            palette, palette
+        );
+
+        let config = parse(&source, Format::Yaml).unwrap();
+        let theme = config.theme().unwrap();
+        assert_eq!(theme.background, RGB8::new(0, 0, 0));
+        assert_eq!(theme.normal.blue, RGB8::new(0, 0, 0xff));
+        assert_eq!(theme.normal.white, RGB8::new(0xff, 0xff, 0xff));
+        // a quoted number is an xterm color index
+        assert_eq!(theme.normal.red, RGB8::new(0xff, 0x87, 0));
+    }
+
+    #[test]
+    fn window_round_trip() {
+        let window = Window {
+            dimensions: Some("100x30".parse().unwrap()),
+            position: Some("-10x20".parse().unwrap()),
+            padding: Some("5x0".parse().unwrap()),
+            dynamic_padding: Some(true),
+            decorations: Some("buttonless".parse().unwrap()),
+            opacity: Some("0.9".parse().unwrap()),
+            startup_mode: Some("maximized".parse().unwrap()),
+            title: Some("it's: #1".to_owned()),
+            dynamic_title: Some(false),
+            class: Some("term,Term".parse().unwrap()),
+        };
+        let config = merge(
+            PartialConfig::new(None, None, Some(window.clone())),
+            PartialConfig::default(),
+        );
+
+        for &format in &[Format::Yaml, Format::Toml] {
+            let source = render(&config, format, false);
+            let parsed = parse(&source, format).unwrap();
+            assert_eq!(
+                parsed.window().unwrap().to_string(),
+                window.to_string(),
+                "{}",
+                source
+            );
+        }
+    }
+}
@@ -0,0 +1,328 @@
+use crate::color::{self, from_linear, to_linear, Oklch};
+use crate::theme::{Theme, ThemeColors};
+use rgb::RGB8;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+use termcolor::{Color, ColorSpec, WriteColor};
+
+/// Colors closer than this (CIEDE2000) are easily mistaken for each other.
+const MIN_DELTA_E: f64 = 10.0;
+
+/// The accent colors, which are told apart by hue alone.
+const ACCENTS: [&str; 6] = ["red", "green", "yellow", "blue", "magenta", "cyan"];
+
+/// How much `adjust` changes the lightness of a color at a time, in OKLab.
+const STEP: f64 = 0.02;
+/// The most steps `adjust` changes a color by at once.
+const MAX_STEPS: u32 = 10;
+/// How many times `adjust` goes over the colors that are still confused.
+const MAX_PASSES: usize = 3;
+/// How much `adjust` weighs changing colors against making them easier to
+/// tell apart.
+const CHANGE_WEIGHT: f64 = 0.05;
+/// Contrast `adjust` keeps with the background, unless a color had less.
+const MIN_CONTRAST: f64 = 3.0;
+
+/// A color vision deficiency.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Deficiency {
+    /// Missing or weak red cones.
+    Protan,
+    /// Missing or weak green cones, the most common one.
+    Deutan,
+    /// Missing or weak blue cones.
+    Tritan,
+}
+
+impl Deficiency {
+    pub const ALL: [Self; 3] = [Self::Protan, Self::Deutan, Self::Tritan];
+
+    /// Simulation of full dichromacy in linear RGB, from Machado, Oliveira and
+    /// Fernandes (2009), at a severity of 1.
+    const fn matrix(self) -> [[f64; 3]; 3] {
+        match self {
+            Self::Protan => [
+                [0.152_286, 1.052_583, -0.204_868],
+                [0.114_503, 0.786_281, 0.099_216],
+                [-0.003_882, -0.048_116, 1.051_998],
+            ],
+            Self::Deutan => [
+                [0.367_322, 0.860_646, -0.227_968],
+                [0.280_085, 0.672_501, 0.047_413],
+                [-0.011_820, 0.042_940, 0.968_881],
+            ],
+            Self::Tritan => [
+                [1.255_528, -0.076_749, -0.178_779],
+                [-0.078_411, 0.930_809, 0.147_602],
+                [0.004_733, 0.691_367, 0.303_900],
+            ],
+        }
+    }
+
+    /// How `rgb` looks with this deficiency.
+    pub fn simulate(self, rgb: RGB8) -> RGB8 {
+        let linear = [to_linear(rgb.r), to_linear(rgb.g), to_linear(rgb.b)];
+        let [r, g, b] = multiply(self.matrix(), linear);
+
+        RGB8::new(from_linear(r), from_linear(g), from_linear(b))
+    }
+
+    /// Full name of the condition, for headings.
+    pub const fn condition(self) -> &'static str {
+        match self {
+            Self::Protan => "Protanopia",
+            Self::Deutan => "Deuteranopia",
+            Self::Tritan => "Tritanopia",
+        }
+    }
+}
+
+impl fmt::Display for Deficiency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Protan => "protan",
+            Self::Deutan => "deutan",
+            Self::Tritan => "tritan",
+        })
+    }
+}
+
+impl FromStr for Deficiency {
+    type Err = DeficiencyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "protan" | "protanopia" => Ok(Self::Protan),
+            "deutan" | "deuteranopia" => Ok(Self::Deutan),
+            "tritan" | "tritanopia" => Ok(Self::Tritan),
+            _ => Err(DeficiencyParseError(s.to_owned())),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DeficiencyParseError(String);
+
+impl fmt::Display for DeficiencyParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown color vision deficiency '{}'; expected protan, deutan or tritan",
+            self.0
+        )
+    }
+}
+
+fn multiply(matrix: [[f64; 3]; 3], v: [f64; 3]) -> [f64; 3] {
+    let row = |r: [f64; 3]| r[0] * v[0] + r[1] * v[1] + r[2] * v[2];
+    [row(matrix[0]), row(matrix[1]), row(matrix[2])]
+}
+
+/// `theme` as seen with `deficiency`.
+pub fn simulate(theme: &Theme, deficiency: Deficiency) -> Theme {
+    let mut simulated = theme.clone();
+    simulated.map_colors(|rgb| deficiency.simulate(rgb));
+    simulated
+}
+
+/// Two accent colors that can be told apart, but not with the deficiency.
+pub struct Confusion {
+    /// Palette the colors are in, `normal` or `bright`.
+    pub section: &'static str,
+    pub colors: (&'static str, &'static str),
+    /// Difference as seen with the deficiency.
+    pub delta_e: f64,
+}
+
+/// The accent colors of `theme` that become hard to tell apart with
+/// `deficiency`.
+pub fn confusions(theme: &Theme, deficiency: Deficiency) -> Vec<Confusion> {
+    let mut confusions = Vec::new();
+
+    for &(section, palette) in &[("normal", &theme.normal), ("bright", &theme.bright)] {
+        for (a, b, delta_e) in confused_pairs(palette, palette, deficiency) {
+            confusions.push(Confusion {
+                section,
+                colors: (ACCENTS[a], ACCENTS[b]),
+                delta_e,
+            });
+        }
+    }
+
+    confusions
+}
+
+/// The accent colors of `palette`, in the order of `ACCENTS`.
+fn accents(palette: &ThemeColors) -> [RGB8; 6] {
+    [
+        palette.red,
+        palette.green,
+        palette.yellow,
+        palette.blue,
+        palette.magenta,
+        palette.cyan,
+    ]
+}
+
+/// Indices into `ACCENTS` of the colors of `palette` that look alike with
+/// `deficiency`, along with how far apart they look. Colors that already
+/// look alike in `original` aren't worth a warning.
+fn confused_pairs(
+    palette: &ThemeColors,
+    original: &ThemeColors,
+    deficiency: Deficiency,
+) -> Vec<(usize, usize, f64)> {
+    let (colors, original) = (accents(palette), accents(original));
+    let seen = colors.map(|color| deficiency.simulate(color));
+
+    let mut pairs = Vec::new();
+    for a in 0..colors.len() {
+        for b in a + 1..colors.len() {
+            let delta_e = color::delta_e(seen[a], seen[b]);
+            if delta_e < MIN_DELTA_E && color::delta_e(original[a], original[b]) >= MIN_DELTA_E {
+                pairs.push((a, b, delta_e));
+            }
+        }
+    }
+    pairs
+}
+
+/// A variant of `theme` whose accent colors can be told apart with
+/// `deficiency`, as far as possible. Colors that are confused are lightened
+/// or darkened, keeping their hue, by as little as it takes.
+pub fn adjust(theme: &Theme, deficiency: Deficiency) -> Theme {
+    let mut adjusted = theme.clone();
+    adjust_palette(&mut adjusted.normal, theme, &theme.normal, deficiency);
+    adjust_palette(&mut adjusted.bright, theme, &theme.bright, deficiency);
+    adjusted
+}
+
+fn adjust_palette(
+    palette: &mut ThemeColors,
+    theme: &Theme,
+    original: &ThemeColors,
+    deficiency: Deficiency,
+) {
+    // how far the palette is from being told apart and from staying readable
+    // on the background, and how much it changed
+    let score = |palette: &ThemeColors| {
+        let confused: f64 = confused_pairs(palette, original, deficiency)
+            .iter()
+            .map(|&(_, _, delta_e)| MIN_DELTA_E - delta_e)
+            .sum();
+        let unreadable: f64 = accents(palette)
+            .iter()
+            .zip(accents(original).iter())
+            .map(|(&color, &original)| {
+                let min = color::contrast(original, theme.background).min(MIN_CONTRAST);
+                (min - color::contrast(color, theme.background)).max(0.0) * MIN_DELTA_E
+            })
+            .sum();
+        let changed: f64 = accents(palette)
+            .iter()
+            .zip(accents(original).iter())
+            .map(|(&color, &original)| color::delta_e(color, original))
+            .sum();
+        confused + unreadable + CHANGE_WEIGHT * changed
+    };
+
+    for _ in 0..MAX_PASSES {
+        let pairs = confused_pairs(palette, original, deficiency);
+        if pairs.is_empty() {
+            return;
+        }
+
+        for (a, b, _) in pairs {
+            let mut best = (score(palette), *palette);
+
+            for step in 1..=MAX_STEPS {
+                for &index in &[b, a] {
+                    for &sign in &[1.0, -1.0] {
+                        let mut candidate = *palette;
+                        let color = accent_mut(&mut candidate, index);
+                        let mut lch = Oklch::from(*color);
+                        lch.l += sign * STEP * f64::from(step);
+                        *color = lch.to_rgb8();
+
+                        let score = score(&candidate);
+                        if score < best.0 {
+                            best = (score, candidate);
+                        }
+                    }
+                }
+            }
+
+            *palette = best.1;
+        }
+    }
+}
+
+/// The accent color of `palette` at `index` into `ACCENTS`.
+fn accent_mut(palette: &mut ThemeColors, index: usize) -> &mut RGB8 {
+    match index {
+        0 => &mut palette.red,
+        1 => &mut palette.green,
+        2 => &mut palette.yellow,
+        3 => &mut palette.blue,
+        4 => &mut palette.magenta,
+        _ => &mut palette.cyan,
+    }
+}
+
+/// Print the normal and bright colors of `theme` next to how they look with
+/// `deficiency`, followed by the colors that become hard to tell apart.
+pub fn print<W: WriteColor>(out: &mut W, theme: &Theme, deficiency: Deficiency) -> io::Result<()> {
+    let simulated = simulate(theme, deficiency);
+
+    out.set_color(ColorSpec::new().set_bold(true))?;
+    writeln!(out, "{} ({})", deficiency.condition(), deficiency)?;
+    out.reset()?;
+
+    write!(out, "{:<10}", "")?;
+    for name in &ThemeColors::NAMES {
+        write!(out, "{:<8}", name)?;
+    }
+    writeln!(out)?;
+
+    let rows = [
+        ("normal", &theme.normal, &simulated.normal),
+        ("bright", &theme.bright, &simulated.bright),
+    ];
+    for &(section, original, seen) in &rows {
+        swatches(out, section, original)?;
+        swatches(out, "  as seen", seen)?;
+    }
+
+    let confusions = confusions(theme, deficiency);
+    for confusion in &confusions {
+        out.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)).set_bold(true))?;
+        write!(out, "warning:")?;
+        out.reset()?;
+        writeln!(
+            out,
+            " {section}.{} and {section}.{} are hard to tell apart (ΔE {:.1})",
+            confusion.colors.0,
+            confusion.colors.1,
+            confusion.delta_e,
+            section = confusion.section
+        )?;
+    }
+    if confusions.is_empty() {
+        writeln!(out, "All accent colors can be told apart.")?;
+    }
+
+    out.flush()
+}
+
+/// Print a row of swatches of `palette`, labeled with `label`.
+fn swatches<W: WriteColor>(out: &mut W, label: &str, palette: &ThemeColors) -> io::Result<()> {
+    write!(out, "{:<10}", label)?;
+    for &(_, c) in &palette.named() {
+        out.set_color(ColorSpec::new().set_bg(Some(Color::Rgb(c.r, c.g, c.b))))?;
+        write!(out, "      ")?;
+        out.reset()?;
+        write!(out, "  ")?;
+    }
+    writeln!(out)
+}
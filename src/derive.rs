@@ -0,0 +1,114 @@
+use crate::color::{self, Oklch};
+use crate::schedule::Period;
+use crate::theme::{CellColor, ColorPair, Theme, ThemeColors};
+use rgb::RGB8;
+
+/// Lightness of the backgrounds of typical dark themes, from the darkest to
+/// the lightest.
+const DARK_BACKGROUNDS: (f64, f64) = (0.15, 0.30);
+/// Lightness of the backgrounds of typical light themes, from the lightest to
+/// the darkest.
+const LIGHT_BACKGROUNDS: (f64, f64) = (0.99, 0.92);
+
+/// Range of contrast a color keeps with the background, for colors that keep
+/// the contrast they had.
+const ANY_CONTRAST: (f64, f64) = (1.0, 21.0);
+/// Contrast the foreground keeps with the background at least, WCAG AA for
+/// normal text.
+const MIN_FOREGROUND_CONTRAST: f64 = 4.5;
+/// Range of contrast of the normal and bright accent colors. The minimum is
+/// WCAG AA for large text. Dark themes often have pastel accents with a lot
+/// of contrast, which would end up nearly black on a light background, hence
+/// the maximum.
+const ACCENT_CONTRAST: (f64, f64) = (3.0, 6.0);
+
+/// Whether `theme` is a light or a dark theme, going by its background.
+pub fn period(theme: &Theme) -> Period {
+    if Oklch::from(theme.background).l < 0.5 {
+        Period::Dark
+    } else {
+        Period::Light
+    }
+}
+
+/// A light or dark counterpart of `theme`. The background is moved to the
+/// other end of the lightness range, and every other color to the other side
+/// of it with about the contrast it had, keeping its hue and chroma. A theme
+/// that already is `period` is returned as it is.
+pub fn variant(theme: &Theme, period: Period) -> Theme {
+    if self::period(theme) == period {
+        return theme.clone();
+    }
+
+    let old_background = theme.background;
+    let mut background = Oklch::from(old_background);
+    background.l = match period {
+        Period::Light => {
+            let t = (background.l - DARK_BACKGROUNDS.0) / (DARK_BACKGROUNDS.1 - DARK_BACKGROUNDS.0);
+            lerp(LIGHT_BACKGROUNDS, t)
+        }
+        Period::Dark => {
+            let t =
+                (LIGHT_BACKGROUNDS.0 - background.l) / (LIGHT_BACKGROUNDS.0 - LIGHT_BACKGROUNDS.1);
+            lerp(DARK_BACKGROUNDS, t)
+        }
+    };
+    let background = background.to_rgb8();
+
+    let map = |rgb: RGB8, (min, max): (f64, f64)| {
+        let lighter = color::luminance(rgb) > color::luminance(old_background);
+        let ratio = color::contrast(rgb, old_background).min(max).max(min);
+        Oklch::from(rgb).with_contrast(background, ratio, !lighter)
+    };
+    let map_colors = |colors: &ThemeColors, accents: (f64, f64)| ThemeColors {
+        // black is the dark gray and white the light one in light themes as
+        // well, so they trade places
+        black: map(colors.white, ANY_CONTRAST),
+        red: map(colors.red, accents),
+        green: map(colors.green, accents),
+        yellow: map(colors.yellow, accents),
+        blue: map(colors.blue, accents),
+        magenta: map(colors.magenta, accents),
+        cyan: map(colors.cyan, accents),
+        white: map(colors.black, ANY_CONTRAST),
+    };
+    let map_pair = |pair: Option<ColorPair>| {
+        let map_cell = |cell: Option<CellColor>| match cell {
+            Some(CellColor::Rgb(rgb)) => Some(CellColor::Rgb(map(rgb, ANY_CONTRAST))),
+            cell => cell,
+        };
+
+        pair.map(|pair| ColorPair {
+            foreground: map_cell(pair.foreground),
+            background: map_cell(pair.background),
+        })
+    };
+
+    let mut indexed_colors = theme.indexed_colors.clone();
+    for indexed in &mut indexed_colors {
+        indexed.color = map(indexed.color, ANY_CONTRAST);
+    }
+
+    Theme {
+        background,
+        foreground: map(theme.foreground, (MIN_FOREGROUND_CONTRAST, 21.0)),
+        normal: map_colors(&theme.normal, ACCENT_CONTRAST),
+        bright: map_colors(&theme.bright, ACCENT_CONTRAST),
+        dim: theme.dim.as_ref().map(|dim| map_colors(dim, ANY_CONTRAST)),
+        indexed_colors,
+        cursor: map_pair(theme.cursor),
+        vi_mode_cursor: map_pair(theme.vi_mode_cursor),
+        selection: map_pair(theme.selection),
+        search_matches: map_pair(theme.search_matches),
+        search_focused_match: map_pair(theme.search_focused_match),
+        hints_start: map_pair(theme.hints_start),
+        hints_end: map_pair(theme.hints_end),
+        footer_bar: map_pair(theme.footer_bar),
+        line_indicator: map_pair(theme.line_indicator),
+    }
+}
+
+/// The value `t` of the way from `a` to `b`, with `t` clamped to 0 to 1.
+fn lerp((a, b): (f64, f64), t: f64) -> f64 {
+    a + (b - a) * t.clamp(0.0, 1.0)
+}
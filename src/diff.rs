@@ -0,0 +1,148 @@
+use std::io;
+use termcolor::{Color, ColorSpec, WriteColor};
+
+/// Lines of context shown around each change.
+const CONTEXT: usize = 3;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Line by line edit script from `old` to `new`, based on their longest
+/// common subsequence. Configs are small, so the quadratic table is fine.
+fn edits<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(Op, &'a str)> {
+    // lcs[i][j] is the length of the LCS of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut edits = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            edits.push((Op::Equal, old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            // deletions first, like diff(1)
+            edits.push((Op::Delete, old[i]));
+            i += 1;
+        } else {
+            edits.push((Op::Insert, new[j]));
+            j += 1;
+        }
+    }
+
+    edits
+}
+
+/// Print a colored unified diff from `old` to `new`. Returns whether there
+/// were any differences.
+pub fn print<W: WriteColor>(
+    out: &mut W,
+    old_name: &str,
+    new_name: &str,
+    old: &str,
+    new: &str,
+) -> io::Result<bool> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let edits = edits(&old, &new);
+
+    if edits.iter().all(|(op, _)| *op == Op::Equal) {
+        return Ok(false);
+    }
+
+    out.set_color(ColorSpec::new().set_bold(true))?;
+    writeln!(out, "--- {}\n+++ {}", old_name, new_name)?;
+    out.reset()?;
+
+    // group changes that are close enough for their context to overlap
+    let changes: Vec<usize> = edits
+        .iter()
+        .enumerate()
+        .filter(|(_, (op, _))| *op != Op::Equal)
+        .map(|(i, _)| i)
+        .collect();
+
+    let mut hunk_start = 0;
+    while hunk_start < changes.len() {
+        let mut hunk_end = hunk_start;
+        while hunk_end + 1 < changes.len()
+            && changes[hunk_end + 1] - changes[hunk_end] <= 2 * CONTEXT
+        {
+            hunk_end += 1;
+        }
+
+        let from = changes[hunk_start].saturating_sub(CONTEXT);
+        let to = (changes[hunk_end] + CONTEXT + 1).min(edits.len());
+        print_hunk(out, &edits, from, to)?;
+
+        hunk_start = hunk_end + 1;
+    }
+
+    out.flush()?;
+    Ok(true)
+}
+
+fn print_hunk<W: WriteColor>(
+    out: &mut W,
+    edits: &[(Op, &str)],
+    from: usize,
+    to: usize,
+) -> io::Result<()> {
+    // line numbers of the first line of the hunk in both files
+    let old_start = edits[..from]
+        .iter()
+        .filter(|(op, _)| *op != Op::Insert)
+        .count();
+    let new_start = edits[..from]
+        .iter()
+        .filter(|(op, _)| *op != Op::Delete)
+        .count();
+    let hunk = &edits[from..to];
+    let old_len = hunk.iter().filter(|(op, _)| *op != Op::Insert).count();
+    let new_len = hunk.iter().filter(|(op, _)| *op != Op::Delete).count();
+
+    // empty ranges start at the line before, as in GNU diff
+    let start = |start: usize, len: usize| if len == 0 { start } else { start + 1 };
+
+    out.set_color(ColorSpec::new().set_fg(Some(Color::Cyan)))?;
+    writeln!(
+        out,
+        "@@ -{},{} +{},{} @@",
+        start(old_start, old_len),
+        old_len,
+        start(new_start, new_len),
+        new_len
+    )?;
+    out.reset()?;
+
+    for (op, line) in hunk {
+        match op {
+            Op::Equal => writeln!(out, " {}", line)?,
+            Op::Delete => {
+                out.set_color(ColorSpec::new().set_fg(Some(Color::Red)))?;
+                writeln!(out, "-{}", line)?;
+                out.reset()?;
+            }
+            Op::Insert => {
+                out.set_color(ColorSpec::new().set_fg(Some(Color::Green)))?;
+                writeln!(out, "+{}", line)?;
+                out.reset()?;
+            }
+        }
+    }
+
+    Ok(())
+}
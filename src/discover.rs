@@ -0,0 +1,150 @@
+use crate::error::{ErrorKind, Result};
+use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Environment variable that overrides the config path, like `--config-file`.
+pub const CONFIG_FILE_VAR: &str = "ALACRITTY_CONF_CONFIG_FILE";
+
+/// File names alacritty looks for, in order of preference. Every location is
+/// searched for the first name before any is searched for the next.
+const NAMES: [&str; 2] = ["alacritty.toml", "alacritty.yml"];
+
+/// How the config path was chosen.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Origin {
+    Flag,
+    Env,
+    /// Found in one of the locations alacritty searches.
+    Found,
+    /// The system-wide config, which is read but never written; changes go to
+    /// a new user config instead.
+    System,
+    /// Nothing was found, so a new config will be created.
+    Default,
+}
+
+#[derive(Debug)]
+pub struct ConfigPath {
+    /// The config to read.
+    pub path: PathBuf,
+    pub origin: Origin,
+}
+
+impl ConfigPath {
+    /// The file changes are written to.
+    pub fn target(&self) -> Result<PathBuf> {
+        match self.origin {
+            Origin::System => default_path(),
+            _ => Ok(self.path.clone()),
+        }
+    }
+}
+
+impl fmt::Display for ConfigPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+
+        match self.origin {
+            Origin::Flag => f.write_str(" (from --config-file)"),
+            Origin::Env => write!(f, " (from ${})", CONFIG_FILE_VAR),
+            Origin::Found => Ok(()),
+            Origin::System => f.write_str(" (system-wide)"),
+            Origin::Default => f.write_str(" (new)"),
+        }
+    }
+}
+
+/// Choose the config to use: `flag` if given, then the path in
+/// `$ALACRITTY_CONF_CONFIG_FILE`, then the first config found in the
+/// locations alacritty searches.
+pub fn locate(flag: Option<&Path>) -> Result<ConfigPath> {
+    if let Some(path) = flag {
+        return Ok(ConfigPath {
+            path: path.to_owned(),
+            origin: Origin::Flag,
+        });
+    }
+
+    if let Some(path) = env::var_os(CONFIG_FILE_VAR).filter(|path| !path.is_empty()) {
+        return Ok(ConfigPath {
+            path: PathBuf::from(path),
+            origin: Origin::Env,
+        });
+    }
+
+    for name in &NAMES {
+        let user = user_candidates(name)?
+            .into_iter()
+            .map(|path| (path, Origin::Found));
+        let system = system_candidate(name).map(|path| (path, Origin::System));
+
+        if let Some((path, origin)) = user.chain(system).find(|(path, _)| path.is_file()) {
+            return Ok(ConfigPath { path, origin });
+        }
+    }
+
+    Ok(ConfigPath {
+        path: default_path()?,
+        origin: Origin::Default,
+    })
+}
+
+/// Where a new config is created if there is none yet. alacritty has only
+/// read TOML since 0.13.
+fn default_path() -> Result<PathBuf> {
+    let mut path = config_home()?;
+    path.push("alacritty");
+    path.push(NAMES[0]);
+    Ok(path)
+}
+
+/// `$XDG_CONFIG_HOME`, falling back to `~/.config`. alacritty uses these on
+/// macOS as well, rather than `~/Library/Application Support`.
+#[cfg(not(windows))]
+pub fn config_home() -> Result<PathBuf> {
+    match env::var_os("XDG_CONFIG_HOME").map(PathBuf::from) {
+        // relative paths are invalid according to the spec
+        Some(path) if path.is_absolute() => Ok(path),
+        _ => Ok(home()?.join(".config")),
+    }
+}
+
+#[cfg(windows)]
+pub fn config_home() -> Result<PathBuf> {
+    Ok(dirs::config_dir().ok_or(ErrorKind::NoConfigDir)?)
+}
+
+fn home() -> Result<PathBuf> {
+    Ok(dirs::home_dir().ok_or(ErrorKind::NoConfigDir)?)
+}
+
+/// Every file named `name` alacritty would load a user config from, in the
+/// order it looks for them.
+#[cfg(not(windows))]
+fn user_candidates(name: &str) -> Result<Vec<PathBuf>> {
+    let config_home = config_home()?;
+    let home = home()?;
+
+    Ok(vec![
+        config_home.join("alacritty").join(name),
+        config_home.join(name),
+        home.join(".config").join("alacritty").join(name),
+        home.join(format!(".{}", name)),
+    ])
+}
+
+#[cfg(windows)]
+fn user_candidates(name: &str) -> Result<Vec<PathBuf>> {
+    Ok(vec![config_home()?.join("alacritty").join(name)])
+}
+
+#[cfg(not(windows))]
+fn system_candidate(name: &str) -> Option<PathBuf> {
+    Some(Path::new("/etc/alacritty").join(name))
+}
+
+#[cfg(windows)]
+const fn system_candidate(_name: &str) -> Option<PathBuf> {
+    None
+}
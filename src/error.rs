@@ -0,0 +1,335 @@
+use crate::theme::{ColorThemeParseError, FontSize, FontSizeParseError};
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use termcolor::{Color, ColorSpec, WriteColor};
+use yaml_rust::ScanError;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Any error that ends the program, along with where it happened.
+#[derive(Debug)]
+pub struct Error {
+    kind: Box<ErrorKind>,
+    path: Option<PathBuf>,
+    line: Option<usize>,
+    column: Option<usize>,
+    /// Printed on its own line after the error.
+    hint: Option<&'static str>,
+}
+
+#[derive(Debug)]
+pub enum ErrorKind {
+    /// The platform's config directory could not be determined.
+    NoConfigDir,
+    /// No theme was passed on the command line.
+    MissingTheme,
+    Io(io::Error),
+    /// The config is not valid YAML.
+    Yaml(String),
+    /// The config is not valid TOML.
+    Toml(String),
+    InvalidColor {
+        key: String,
+        value: String,
+    },
+    /// A value has the wrong type, e.g. a string where a number is expected.
+    InvalidValue {
+        key: String,
+        expected: &'static str,
+    },
+    OutOfRange {
+        key: String,
+        value: String,
+        min: String,
+        max: String,
+    },
+    UnknownTheme(String),
+    /// A theme file without a complete theme in it.
+    NoTheme,
+    /// A config imports itself, directly or through other imports.
+    ImportCycle(PathBuf),
+    /// Imports are nested deeper than alacritty allows.
+    ImportDepth(usize),
+    /// No backup has the given id.
+    UnknownBackup(String),
+    /// A backup in another format than the config it would replace.
+    BackupFormat(PathBuf),
+    UnknownProfile(String),
+    /// A profile name that can't be used as a file name.
+    InvalidProfileName(String),
+    /// An image that can't be decoded.
+    Image(String),
+    /// An image without any opaque pixels to take colors from.
+    EmptyImage,
+    NotEnoughHistory {
+        requested: usize,
+        available: usize,
+    },
+}
+
+impl Error {
+    pub fn new(kind: ErrorKind) -> Self {
+        Self {
+            kind: Box::new(kind),
+            path: None,
+            line: None,
+            column: None,
+            hint: None,
+        }
+    }
+
+    /// Attach the file the error happened in, unless one is already set.
+    pub fn in_file<P: AsRef<Path>>(mut self, path: P) -> Self {
+        if self.path.is_none() {
+            self.path = Some(path.as_ref().to_owned());
+        }
+        self
+    }
+
+    /// Attach the (1-based) line the error happened on.
+    pub const fn at_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    pub const fn with_hint(mut self, hint: &'static str) -> Self {
+        self.hint = Some(hint);
+        self
+    }
+
+    pub fn out_of_range<K, V, R>(key: K, value: V, min: R, max: R) -> Self
+    where
+        K: Into<String>,
+        V: ToString,
+        R: ToString,
+    {
+        Self::new(ErrorKind::OutOfRange {
+            key: key.into(),
+            value: value.to_string(),
+            min: min.to_string(),
+            max: max.to_string(),
+        })
+    }
+
+    /// Print the error to `out` in the same style as clap's errors.
+    pub fn report<W: WriteColor>(&self, out: &mut W) -> io::Result<()> {
+        out.set_color(ColorSpec::new().set_fg(Some(Color::Red)).set_bold(true))?;
+        write!(out, "error:")?;
+        out.reset()?;
+
+        if let ErrorKind::MissingTheme = *self.kind {
+            write!(out, " You must provide a value for '")?;
+            out.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)))?;
+            write!(out, "<theme>")?;
+            out.reset()?;
+
+            write!(out, "'\nUse ")?;
+            out.set_color(ColorSpec::new().set_fg(Some(Color::Green)))?;
+            write!(out, "-l")?;
+            out.reset()?;
+            write!(out, "/")?;
+            out.set_color(ColorSpec::new().set_fg(Some(Color::Green)))?;
+            write!(out, "--list-themes")?;
+            out.reset()?;
+            writeln!(out, " to view the available themes.")?;
+
+            return out.flush();
+        }
+
+        if let Some(location) = self.location() {
+            out.set_color(ColorSpec::new().set_bold(true))?;
+            write!(out, " {}:", location)?;
+            out.reset()?;
+        }
+
+        writeln!(out, " {}", self.kind)?;
+        if let Some(hint) = self.hint {
+            writeln!(out, "{}", hint)?;
+        }
+
+        out.flush()
+    }
+
+    /// `path:line:column`, with whichever parts are known.
+    fn location(&self) -> Option<String> {
+        let mut location = match (&self.path, self.line) {
+            (None, None) => return None,
+            (Some(path), None) => return Some(path.display().to_string()),
+            (None, Some(line)) => format!("line {}", line),
+            (Some(path), Some(line)) => format!("{}:{}", path.display(), line),
+        };
+
+        if let Some(column) = self.column {
+            location.push_str(&format!(":{}", column));
+        }
+
+        Some(location)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.location() {
+            Some(location) => write!(f, "{}: {}", location, self.kind),
+            None => write!(f, "{}", self.kind),
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoConfigDir => {
+                f.write_str("could not determine the config directory; make sure $HOME is set")
+            }
+            Self::MissingTheme => f.write_str("You must provide a value for '<theme>'"),
+            Self::Io(ref e) => write!(f, "{}", e),
+            Self::Yaml(ref e) => write!(f, "invalid YAML: {}", e),
+            Self::Toml(ref e) => write!(f, "invalid TOML: {}", e),
+            Self::InvalidColor { ref key, ref value } => {
+                write!(f, "invalid color '{}' for {}", value, key)
+            }
+            Self::InvalidValue { ref key, expected } => {
+                write!(f, "invalid value for {}; expected {}", key, expected)
+            }
+            Self::OutOfRange {
+                ref key,
+                ref value,
+                ref min,
+                ref max,
+            } => write!(
+                f,
+                "{} {} is out of range; must be between {} and {}",
+                key, value, min, max
+            ),
+            Self::UnknownTheme(ref t) => write!(f, "Unknown theme: {}", t),
+            Self::NoTheme => f.write_str(
+                "no theme found; a theme needs at least the primary, normal and bright colors",
+            ),
+            Self::ImportCycle(ref path) => write!(
+                f,
+                "import cycle: {} imports itself through its imports",
+                path.display()
+            ),
+            Self::ImportDepth(limit) => write!(f, "imports are nested more than {} deep", limit),
+            Self::UnknownBackup(ref id) => write!(
+                f,
+                "no backup with id '{}'; use `alacritty-conf history` to list them",
+                id
+            ),
+            Self::BackupFormat(ref path) => write!(
+                f,
+                "backup {} is in another format than the config",
+                path.display()
+            ),
+            Self::UnknownProfile(ref name) => write!(
+                f,
+                "no profile named '{}'; use `alacritty-conf profile list` to list them",
+                name
+            ),
+            Self::InvalidProfileName(ref name) => write!(
+                f,
+                "invalid profile name '{}'; names can't be empty, start with '.' or contain \
+                 slashes",
+                name
+            ),
+            Self::Image(ref e) => write!(f, "invalid PNG image: {}", e),
+            Self::EmptyImage => f.write_str("the image has no opaque pixels to take colors from"),
+            Self::NotEnoughHistory {
+                requested,
+                available,
+            } => write!(
+                f,
+                "can't go back {} version(s); there are {} older version(s)",
+                requested, available
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        Self::new(kind)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Self::new(ErrorKind::Io(e))
+    }
+}
+
+impl From<ScanError> for Error {
+    fn from(e: ScanError) -> Self {
+        // ScanError only exposes its message through Display, which appends
+        // the location we already report separately
+        let message = e.to_string();
+        let info = message
+            .rsplit_once(" at line ")
+            .map_or(message.as_str(), |(info, _)| info);
+
+        Self {
+            kind: Box::new(ErrorKind::Yaml(info.to_owned())),
+            path: None,
+            line: Some(e.marker().line()),
+            column: Some(e.marker().col() + 1),
+            hint: None,
+        }
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(e: toml::de::Error) -> Self {
+        // like ScanError, the message ends with the location
+        let message = e.to_string();
+        let info = message
+            .rsplit_once(" at line ")
+            .map_or(message.as_str(), |(info, _)| info);
+        let (line, column) = match e.line_col() {
+            Some((line, column)) => (Some(line + 1), Some(column + 1)),
+            None => (None, None),
+        };
+
+        Self {
+            kind: Box::new(ErrorKind::Toml(info.to_owned())),
+            path: None,
+            line,
+            column,
+            hint: None,
+        }
+    }
+}
+
+impl From<png::DecodingError> for Error {
+    fn from(e: png::DecodingError) -> Self {
+        match e {
+            png::DecodingError::IoError(e) => Self::from(e),
+            e => Self::new(ErrorKind::Image(e.to_string())),
+        }
+    }
+}
+
+impl From<FontSizeParseError> for Error {
+    fn from(e: FontSizeParseError) -> Self {
+        match e {
+            FontSizeParseError::FloatParse(_) => Self::new(ErrorKind::InvalidValue {
+                key: "font.size".to_owned(),
+                expected: "a number",
+            }),
+            FontSizeParseError::OutOfRange(size) => {
+                Self::out_of_range("font.size", size, FontSize::MIN, FontSize::MAX)
+            }
+        }
+    }
+}
+
+impl From<ColorThemeParseError> for Error {
+    fn from(e: ColorThemeParseError) -> Self {
+        match e {
+            ColorThemeParseError::UnknownTheme(t) => Self::new(ErrorKind::UnknownTheme(t)),
+        }
+    }
+}
@@ -0,0 +1,440 @@
+use crate::color::{self, Oklab, Oklch};
+use crate::error::{Error, ErrorKind, Result};
+use crate::schedule::Period;
+use crate::theme::{CellColor, ColorPair, Theme, ThemeColors};
+use rgb::RGB8;
+use std::cmp::Reverse;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+/// Hues of the accent colors in OKLCH, in the order red, green, yellow,
+/// blue, magenta and cyan.
+const HUES: [f64; 6] = [25.0, 145.0, 95.0, 260.0, 330.0, 195.0];
+
+/// Colors with less chroma than this count as gray.
+const MIN_CHROMA: f64 = 0.03;
+/// Chroma of the accent colors, unless the seed or image has them more
+/// colorful.
+const ACCENT_CHROMA: (f64, f64) = (0.08, 0.18);
+/// Chroma of the background and the grays, which are tinted by the seed.
+const TINT_CHROMA: f64 = 0.02;
+
+/// Minimum contrast of the foreground with the background, WCAG AAA.
+const FOREGROUND_CONTRAST: f64 = 7.0;
+/// Minimum contrast of every other color but the shade of the background,
+/// WCAG AA.
+const TEXT_CONTRAST: f64 = 4.5;
+
+/// Number of colors an image is reduced to.
+const CLUSTERS: usize = 8;
+/// Most pixels of an image that are looked at; larger images are sampled.
+const MAX_PIXELS: usize = 16384;
+/// Iterations of k-means.
+const ITERATIONS: usize = 16;
+/// How far the hue of a color of an image can be from that of an accent
+/// color to be used for it.
+const MAX_HUE_DISTANCE: f64 = 40.0;
+
+/// Lightness of the colors of a theme.
+struct Lightness {
+    background: f64,
+    foreground: f64,
+    normal: f64,
+    bright: f64,
+    /// Black, bright black, white and bright white. In light themes white
+    /// is the shade of the background, and bright white is a gray for text
+    /// so that it stays readable.
+    grays: [f64; 4],
+}
+
+impl Lightness {
+    const fn of(period: Period) -> Self {
+        match period {
+            Period::Dark => Self {
+                background: 0.22,
+                foreground: 0.92,
+                normal: 0.72,
+                bright: 0.80,
+                grays: [0.30, 0.55, 0.85, 0.97],
+            },
+            Period::Light => Self {
+                background: 0.97,
+                foreground: 0.30,
+                normal: 0.52,
+                bright: 0.46,
+                grays: [0.30, 0.42, 0.88, 0.55],
+            },
+        }
+    }
+}
+
+/// A theme built around `seed`: the background and grays are tinted with
+/// it, the accent color closest to it in hue is replaced by it, and the
+/// other accents lean towards it.
+pub fn from_seed(seed: RGB8, period: Period) -> Theme {
+    let seed = Oklch::from(seed);
+    let chroma = seed.c.max(ACCENT_CHROMA.0).min(ACCENT_CHROMA.1);
+
+    let mut accents = [Oklch {
+        l: 0.0,
+        c: chroma,
+        h: 0.0,
+    }; 6];
+    for (accent, &hue) in accents.iter_mut().zip(HUES.iter()) {
+        accent.h = hue;
+    }
+
+    // a gray seed has no hue to lean towards
+    if seed.c >= MIN_CHROMA {
+        let nearest = nearest_accent(seed.h);
+        let shift = hue_distance(seed.h, HUES[nearest]);
+
+        for accent in &mut accents {
+            accent.h = (accent.h + shift / 2.0).rem_euclid(360.0);
+        }
+        accents[nearest].h = seed.h;
+    }
+
+    palette(seed, accents, period)
+}
+
+/// A theme made of the dominant colors of the PNG image at `path`, like
+/// pywal does. The most common color tints the background, and each accent
+/// color takes its hue from the most common color of the image close to it.
+pub fn from_image(path: &Path, period: Period) -> Result<Theme> {
+    let pixels = decode(path).map_err(|e| e.in_file(path))?;
+    if pixels.is_empty() {
+        return Err(Error::new(ErrorKind::EmptyImage).in_file(path));
+    }
+
+    let mut clusters = k_means(&pixels);
+    clusters.sort_by_key(|&(_, count)| Reverse(count));
+    let colors: Vec<Oklch> = clusters.iter().map(|&(lab, _)| Oklch::from(lab)).collect();
+
+    let chromatic: Vec<Oklch> = colors
+        .iter()
+        .copied()
+        .filter(|color| color.c >= MIN_CHROMA)
+        .collect();
+    let chroma = if chromatic.is_empty() {
+        ACCENT_CHROMA.0
+    } else {
+        chromatic.iter().map(|color| color.c).sum::<f64>() / chromatic.len() as f64
+    };
+
+    let mut accents = [Oklch {
+        l: 0.0,
+        c: chroma.max(ACCENT_CHROMA.0).min(ACCENT_CHROMA.1),
+        h: 0.0,
+    }; 6];
+    for (i, (accent, &hue)) in accents.iter_mut().zip(HUES.iter()).enumerate() {
+        // the colors are sorted by how common they are, and each is only
+        // used for the accent it's closest to, so that no two are the same
+        match chromatic.iter().find(|color| {
+            hue_distance(color.h, hue).abs() <= MAX_HUE_DISTANCE && nearest_accent(color.h) == i
+        }) {
+            Some(color) => {
+                accent.h = color.h;
+                accent.c = color.c.max(ACCENT_CHROMA.0);
+            }
+            None => accent.h = hue,
+        }
+    }
+
+    Ok(palette(colors[0], accents, period))
+}
+
+/// Build a theme with a background and grays tinted with the hue of `tint`,
+/// and the given accent colors, whose lightness is chosen to suit the
+/// background. Every color used for text gets enough contrast with the
+/// background.
+fn palette(tint: Oklch, accents: [Oklch; 6], period: Period) -> Theme {
+    let lightness = Lightness::of(period);
+    let gray = |l: f64| {
+        Oklch {
+            l,
+            c: tint.c.min(TINT_CHROMA),
+            h: tint.h,
+        }
+        .to_rgb8()
+    };
+
+    let background = gray(lightness.background);
+    // text is lighter than the background in dark themes
+    let ensure = |color: RGB8, ratio: f64| {
+        if color::contrast(color, background) >= ratio {
+            color
+        } else {
+            Oklch::from(color).with_contrast(background, ratio, period == Period::Dark)
+        }
+    };
+
+    let colors = |l: f64, grays: [RGB8; 2]| {
+        let [red, green, yellow, blue, magenta, cyan] =
+            accents.map(|accent| ensure(Oklch { l, ..accent }.to_rgb8(), TEXT_CONTRAST));
+
+        ThemeColors {
+            black: grays[0],
+            red,
+            green,
+            yellow,
+            blue,
+            magenta,
+            cyan,
+            white: grays[1],
+        }
+    };
+
+    let [black, bright_black, white, bright_white] = lightness.grays.map(gray);
+    // the gray that's the shade of the background isn't meant for text
+    let (black, white) = match period {
+        Period::Dark => (black, ensure(white, TEXT_CONTRAST)),
+        Period::Light => (ensure(black, TEXT_CONTRAST), white),
+    };
+    let bright_black = ensure(bright_black, TEXT_CONTRAST);
+    let bright_white = ensure(bright_white, TEXT_CONTRAST);
+
+    Theme {
+        background,
+        foreground: ensure(gray(lightness.foreground), FOREGROUND_CONTRAST),
+        normal: colors(lightness.normal, [black, white]),
+        bright: colors(lightness.bright, [bright_black, bright_white]),
+        dim: None,
+        indexed_colors: Vec::new(),
+        cursor: Some(ColorPair::new(
+            CellColor::CellBackground,
+            CellColor::CellForeground,
+        )),
+        vi_mode_cursor: None,
+        selection: None,
+        search_matches: None,
+        search_focused_match: None,
+        hints_start: None,
+        hints_end: None,
+        footer_bar: None,
+        line_indicator: None,
+    }
+}
+
+/// Index of the accent color whose hue is closest to `hue`.
+fn nearest_accent(hue: f64) -> usize {
+    (0..HUES.len())
+        .min_by(|&a, &b| {
+            hue_distance(hue, HUES[a])
+                .abs()
+                .partial_cmp(&hue_distance(hue, HUES[b]).abs())
+                .expect("hues aren't NaN")
+        })
+        .expect("there are accents")
+}
+
+/// Signed difference between two hues in degrees, from -180 to 180.
+fn hue_distance(a: f64, b: f64) -> f64 {
+    (a - b + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// Reads the color and alpha of a pixel from its bytes.
+type ReadPixel = fn(&[u8]) -> (RGB8, u8);
+
+/// The opaque pixels of the PNG image at `path`, in OKLab. Large images are
+/// sampled evenly.
+fn decode(path: &Path) -> Result<Vec<Oklab>> {
+    let mut decoder = png::Decoder::new(BufReader::new(File::open(path)?));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info()?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer)?;
+    let bytes = &buffer[..info.buffer_size()];
+
+    let (channels, rgba): (usize, ReadPixel) = match info.color_type {
+        png::ColorType::Grayscale => (1, |p| (RGB8::new(p[0], p[0], p[0]), 255)),
+        png::ColorType::GrayscaleAlpha => (2, |p| (RGB8::new(p[0], p[0], p[0]), p[1])),
+        png::ColorType::Rgb => (3, |p| (RGB8::new(p[0], p[1], p[2]), 255)),
+        png::ColorType::Rgba => (4, |p| (RGB8::new(p[0], p[1], p[2]), p[3])),
+        // expanded to RGB by the decoder
+        png::ColorType::Indexed => return Err(ErrorKind::Image("unexpected palette".into()).into()),
+    };
+
+    let count = bytes.len() / channels;
+    let stride = (count / MAX_PIXELS).max(1);
+
+    Ok(bytes
+        .chunks_exact(channels)
+        .step_by(stride)
+        .map(rgba)
+        .filter(|&(_, alpha)| alpha >= 128)
+        .map(|(rgb, _)| Oklab::from(rgb))
+        .collect())
+}
+
+/// Group `pixels` into at most `CLUSTERS` colors with k-means, returning
+/// the mean of each group and how many pixels are in it.
+fn k_means(pixels: &[Oklab]) -> Vec<(Oklab, usize)> {
+    let distance =
+        |a: &Oklab, b: &Oklab| (a.l - b.l).powi(2) + (a.a - b.a).powi(2) + (a.b - b.b).powi(2);
+    let nearest = |centroids: &[Oklab], pixel: &Oklab| {
+        (0..centroids.len())
+            .min_by(|&a, &b| {
+                distance(&centroids[a], pixel)
+                    .partial_cmp(&distance(&centroids[b], pixel))
+                    .expect("colors aren't NaN")
+            })
+            .expect("there is a centroid")
+    };
+
+    // start from colors far apart, so that the result doesn't depend on
+    // chance and small groups of colorful pixels aren't lost
+    let mut centroids = vec![pixels[0]];
+    while centroids.len() < CLUSTERS {
+        let (farthest, max) = pixels
+            .iter()
+            .map(|pixel| distance(&centroids[nearest(&centroids, pixel)], pixel))
+            .enumerate()
+            .fold(
+                (0, 0.0),
+                |best, (i, d)| if d > best.1 { (i, d) } else { best },
+            );
+        if max == 0.0 {
+            break;
+        }
+        centroids.push(pixels[farthest]);
+    }
+
+    let mut counts = vec![0; centroids.len()];
+    for _ in 0..ITERATIONS {
+        let mut sums = vec![(0.0, 0.0, 0.0); centroids.len()];
+        counts = vec![0; centroids.len()];
+        for pixel in pixels {
+            let i = nearest(&centroids, pixel);
+            sums[i].0 += pixel.l;
+            sums[i].1 += pixel.a;
+            sums[i].2 += pixel.b;
+            counts[i] += 1;
+        }
+
+        for ((centroid, sum), &count) in centroids.iter_mut().zip(&sums).zip(&counts) {
+            if count > 0 {
+                let n = count as f64;
+                *centroid = Oklab {
+                    l: sum.0 / n,
+                    a: sum.1 / n,
+                    b: sum.2 / n,
+                };
+            }
+        }
+    }
+
+    centroids
+        .into_iter()
+        .zip(counts)
+        .filter(|&(_, count)| count > 0)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const PERIODS: [Period; 2] = [Period::Dark, Period::Light];
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name)
+    }
+
+    /// The normal accent colors of `theme`, in the order of `HUES`.
+    fn accents(theme: &Theme) -> Vec<Oklch> {
+        theme.normal.named()[1..7]
+            .iter()
+            .map(|&(_, color)| Oklch::from(color))
+            .collect()
+    }
+
+    /// Check the contrast every color but the shade of the background is
+    /// guaranteed to have.
+    fn assert_readable(theme: &Theme, period: Period) {
+        let contrast = |color: RGB8| color::contrast(color, theme.background);
+        assert!(contrast(theme.foreground) >= FOREGROUND_CONTRAST);
+
+        let shade = match period {
+            Period::Dark => "black",
+            Period::Light => "white",
+        };
+        for (section, colors) in [("normal", theme.normal), ("bright", theme.bright)] {
+            for (name, color) in colors.named() {
+                if section == "normal" && name == shade {
+                    continue;
+                }
+                assert!(
+                    contrast(color) >= TEXT_CONTRAST,
+                    "{}.{} of the {:?} theme has a contrast of {:.2}",
+                    section,
+                    name,
+                    period,
+                    contrast(color)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn single_color() {
+        let seed = Oklch::from(RGB8::new(0x5e, 0x81, 0xac));
+        let nearest = nearest_accent(seed.h);
+
+        for &period in &PERIODS {
+            let theme = from_image(&fixture("single_color.png"), period).unwrap();
+            assert_readable(&theme, period);
+
+            // the background is tinted with the color, and the accent closest
+            // to it takes its hue
+            let background = Oklch::from(theme.background);
+            assert!(hue_distance(background.h, seed.h).abs() < 15.0);
+            assert!(hue_distance(accents(&theme)[nearest].h, seed.h).abs() < 5.0);
+        }
+    }
+
+    #[test]
+    fn transparent_pixels_are_ignored() {
+        // the same color as single_color.png, next to red pixels that are
+        // (mostly) transparent
+        for &period in &PERIODS {
+            let theme = from_image(&fixture("alpha.png"), period).unwrap();
+            let opaque = from_image(&fixture("single_color.png"), period).unwrap();
+
+            assert_readable(&theme, period);
+            assert_eq!(theme.to_string(), opaque.to_string());
+        }
+    }
+
+    #[test]
+    fn grayscale() {
+        for &period in &PERIODS {
+            let theme = from_image(&fixture("grayscale.png"), period).unwrap();
+            assert_readable(&theme, period);
+
+            // nothing to tint the grays with
+            for gray in [theme.background, theme.foreground, theme.normal.black] {
+                assert!(gray.r == gray.g && gray.g == gray.b, "{:?}", gray);
+            }
+            // nor any hue for the accents to take
+            for (accent, &hue) in accents(&theme).iter().zip(HUES.iter()) {
+                assert!(hue_distance(accent.h, hue).abs() < 5.0);
+                assert!(accent.c >= MIN_CHROMA);
+            }
+        }
+    }
+
+    #[test]
+    fn seed() {
+        for &period in &PERIODS {
+            assert_readable(&from_seed(RGB8::new(0x5e, 0x81, 0xac), period), period);
+            // a gray seed, and one about as light as the foreground
+            assert_readable(&from_seed(RGB8::new(0x80, 0x80, 0x80), period), period);
+            assert_readable(&from_seed(RGB8::new(0xff, 0xff, 0x00), period), period);
+        }
+    }
+}
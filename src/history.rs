@@ -0,0 +1,230 @@
+use crate::backup::{self, Backup};
+use crate::config::{self, Format, PartialConfig};
+use crate::error::{Error, ErrorKind, Result};
+use chrono::{DateTime, Local};
+use std::fs;
+use std::io;
+use std::path::Path;
+use termcolor::{Color, ColorSpec, WriteColor};
+
+/// Prefix of the header written at the top of every generated config.
+const HEADER_PREFIX: &str = "# generated by alacritty-conf (";
+
+/// The header to write at the top of a config generated now.
+pub fn header() -> String {
+    format!("{}{})", HEADER_PREFIX, chrono::Utc::now().to_rfc2822())
+}
+
+/// The config without its header, if it has one.
+pub fn strip_header(source: &str) -> &str {
+    match source.strip_prefix(HEADER_PREFIX) {
+        Some(rest) => rest
+            .split_once('\n')
+            .map_or("", |(_, body)| body.trim_start_matches('\n')),
+        None => source,
+    }
+}
+
+/// When a config was generated, according to its header.
+fn generated(source: &str) -> Option<DateTime<Local>> {
+    let date = source
+        .lines()
+        .next()?
+        .strip_prefix(HEADER_PREFIX)?
+        .strip_suffix(')')?;
+
+    DateTime::parse_from_rfc2822(date)
+        .ok()
+        .map(|date| date.with_timezone(&Local))
+}
+
+/// One line summary of the theme, font and window size of a config.
+pub fn summary(config: &PartialConfig) -> String {
+    let theme = match config.theme().map(|theme| theme.preset()) {
+        Some(Some((preset, false))) => preset.to_string(),
+        Some(Some((preset, true))) => format!("{} (inverted)", preset),
+        Some(None) => "custom theme".to_owned(),
+        None => "no theme".to_owned(),
+    };
+    let font = match config.font() {
+        Some(font) => match (&font.family, font.size) {
+            (Some(family), Some(size)) => format!("{} {}", family, size),
+            (Some(family), None) => family.clone(),
+            (None, Some(size)) => format!("size {}", size),
+            (None, None) => "default font".to_owned(),
+        },
+        None => "default font".to_owned(),
+    };
+    let dimensions = match config.window().and_then(|window| window.dimensions) {
+        Some(dimensions) => format!("{}x{}", dimensions.columns, dimensions.lines),
+        None => "default size".to_owned(),
+    };
+
+    format!("{}, {}, {}", theme, font, dimensions)
+}
+
+fn read(path: &Path) -> Result<String> {
+    fs::read_to_string(path).map_err(|e| Error::from(e).in_file(path))
+}
+
+fn write_version<W: WriteColor>(out: &mut W, number: usize, id: &str, path: &Path) -> Result<()> {
+    out.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)))?;
+    write!(out, "{:>3}  {:<19}", number, id)?;
+    out.reset()?;
+
+    let source = match read(path) {
+        Ok(source) => source,
+        Err(_) => {
+            writeln!(out, "  (unreadable)")?;
+            return Ok(());
+        }
+    };
+
+    match generated(&source) {
+        Some(date) => write!(out, "  {}", date.format("%Y-%m-%d %H:%M:%S"))?,
+        None => write!(out, "  {:<19}", "not generated")?,
+    }
+
+    // imports are relative to the config, so they can't be followed from a
+    // backup
+    let config = if number == 0 {
+        config::parse_file(path, &source)
+    } else {
+        config::parse(&source, Format::of(path))
+    };
+
+    match config {
+        Ok(config) => writeln!(out, "  {}", summary(&config))?,
+        Err(e) => writeln!(out, "  (can't be parsed: {})", e)?,
+    }
+
+    Ok(())
+}
+
+/// Print the current config and every backup of it, newest first.
+pub fn print<W: WriteColor>(path: &Path, out: &mut W) -> Result<()> {
+    let backups = backup::list(path)?;
+
+    out.set_color(ColorSpec::new().set_bold(true))?;
+    writeln!(
+        out,
+        "{:>3}  {:<19}  {:<19}  summary",
+        "#", "id", "generated"
+    )?;
+    out.reset()?;
+
+    write_version(out, 0, "current", path)?;
+    for (i, backup) in backups.iter().rev().enumerate() {
+        write_version(out, i + 1, &backup.id, &backup.path)?;
+    }
+
+    if backups.is_empty() {
+        writeln!(
+            out,
+            "\nNo backups yet; one is made every time the config is changed."
+        )?;
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+/// Find a backup by its id, or by its number in `history`.
+fn find(path: &Path, id: &str) -> Result<Backup> {
+    let mut backups = backup::list(path)?;
+
+    let index = match id.parse::<usize>() {
+        Ok(n) if n >= 1 && n <= backups.len() => Some(backups.len() - n),
+        Ok(n) => {
+            return Err(ErrorKind::NotEnoughHistory {
+                requested: n,
+                available: backups.len(),
+            }
+            .into())
+        }
+        Err(_) => backups.iter().position(|backup| backup.id == id),
+    };
+
+    match index {
+        Some(i) => Ok(backups.swap_remove(i)),
+        None => Err(ErrorKind::UnknownBackup(id.to_owned()).into()),
+    }
+}
+
+/// Contents of `backup`, to replace the config at `path` with. A backup in
+/// another format than the config is refused, since alacritty would fail to
+/// load it.
+fn read_backup(path: &Path, backup: &Backup) -> Result<String> {
+    if Format::of(&backup.path) != Format::of(path) {
+        return Err(ErrorKind::BackupFormat(backup.path.clone()).into());
+    }
+
+    read(&backup.path)
+}
+
+/// Replace the config with one of its backups. The config being replaced is
+/// backed up as well, so a restore can itself be undone.
+pub fn restore(path: &Path, id: &str, keep: usize) -> Result<Backup> {
+    let backup = find(path, id)?;
+
+    // read before writing, since writing may prune the backup
+    let source = read_backup(path, &backup)?;
+    backup::write(path, &source, keep)?;
+    forget_undo(path)?;
+
+    Ok(backup)
+}
+
+/// Roll back to the config from `n` changes ago.
+///
+/// Consecutive undos keep stepping back through the history: the position
+/// of the last undo is remembered for as long as the config is left as it
+/// restored it, and the versions being undone aren't backed up again.
+pub fn undo(path: &Path, n: usize, keep: usize) -> Result<Backup> {
+    let mut backups = backup::list(path)?;
+    let cursor = backup::undo_cursor(path)?;
+
+    let undone = last_undo(path, &cursor, &backups);
+    let start = undone.unwrap_or(backups.len());
+    let index = match start.checked_sub(n) {
+        Some(index) if n > 0 => index,
+        _ => {
+            return Err(ErrorKind::NotEnoughHistory {
+                requested: n,
+                available: start,
+            }
+            .into())
+        }
+    };
+    let backup = backups.swap_remove(index);
+
+    let source = read_backup(path, &backup)?;
+    // the config is the version the last undo went back to, which already
+    // has a backup
+    let keep = if undone.is_some() { 0 } else { keep };
+    backup::write(path, &source, keep)?;
+    fs::write(&cursor, &backup.id).map_err(|e| Error::from(e).in_file(&cursor))?;
+
+    Ok(backup)
+}
+
+/// Position among `backups` of the version the last undo went back to, if
+/// the config hasn't changed since.
+fn last_undo(path: &Path, cursor: &Path, backups: &[Backup]) -> Option<usize> {
+    let id = fs::read_to_string(cursor).ok()?;
+    let index = backups.iter().position(|backup| backup.id == id)?;
+
+    let current = fs::read_to_string(path).ok()?;
+    (fs::read_to_string(&backups[index].path).ok()? == current).then_some(index)
+}
+
+/// Start the next undo from the current config again.
+fn forget_undo(path: &Path) -> Result<()> {
+    let cursor = backup::undo_cursor(path)?;
+
+    match fs::remove_file(&cursor) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(Error::from(e).in_file(&cursor)),
+    }
+}
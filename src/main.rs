@@ -1,41 +1,82 @@
 #![warn(clippy::all)]
 #![allow(clippy::missing_docs_in_private_items)]
-#![feature(const_option)]
 
+mod backup;
+mod blend;
+mod check;
+mod color;
+mod color_syntax;
+mod compare;
 mod config;
+mod cvd;
+mod derive;
+mod diff;
+mod discover;
+mod error;
 mod event;
+mod generate;
+mod history;
+mod profile;
+mod schedule;
+mod slot;
 mod theme;
+mod transform;
+mod watch;
 
-use crate::config::{Config, PartialConfig};
+use crate::backup::Backup;
+use crate::check::Level;
+use crate::color_syntax::parse_color;
+use crate::config::{Config, Format, PartialConfig, ToHex};
+use crate::cvd::Deficiency;
+use crate::discover::{ConfigPath, Origin};
+use crate::error::{Error, ErrorKind, Result};
 use crate::event::{Event, Events};
-use crate::theme::{ColorTheme, Font, FontSize, Invert, Theme, Window};
-use std::fs::{self, File};
+use crate::schedule::{Period, Schedule};
+use crate::slot::Override;
+use crate::theme::{Class, ColorTheme, Decorations, Dimensions, Font, FontSizeArg};
+use crate::theme::{Opacity, Padding, Position, StartupMode, Theme, Window};
+use crate::transform::{Hue, Invert, InvertMode, Lighten, Saturate, Transform, Warmth};
+use crate::watch::Watcher;
+use chrono::{Local, NaiveTime};
+use rgb::RGB8;
+use std::collections::HashSet;
+use std::env;
+use std::fs;
 use std::io::{self, Write};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::thread;
+use std::time::Duration;
 use structopt::clap::AppSettings::ColoredHelp;
+use structopt::clap::{ArgGroup, ArgMatches};
 use structopt::StructOpt;
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 
+use termion::event::Key;
 use termion::raw::IntoRawMode;
 use tui::backend::TermionBackend;
 use tui::layout::{Constraint, Direction, Layout};
-use tui::widgets::{Block, Borders, Widget};
+use tui::widgets::{Block, Borders};
 use tui::Terminal;
 
 #[derive(Debug, StructOpt)]
 #[structopt(setting = ColoredHelp)]
 struct Args {
-    /// Color theme. Use -l/--list-themes to view available themes.
+    /// Color theme: a preset, or a file with a `colors` section. Use
+    /// -l/--list-themes to view the presets.
     #[structopt()]
-    theme: Option<ColorTheme>,
+    theme: Option<String>,
 
     // TODO check if it's a valid font before setting it
     /// Font family of terminal to set
     #[structopt(short, long)]
     font: Option<String>,
 
-    /// Font size (integer)
-    #[structopt(short = "s", long)]
-    font_size: Option<u16>,
+    /// Font size. Prefix with '+' or '-' to change the current size, e.g. +2
+    /// or -1.5
+    #[structopt(short = "s", long, allow_hyphen_values = true)]
+    font_size: Option<FontSizeArg>,
 
     /// Print all available themes
     #[structopt(short, long)]
@@ -45,172 +86,1320 @@ struct Args {
     #[structopt(short = "I", long)]
     invert_colors: bool,
 
-    /// Dimensions of window size
-    #[structopt(long, short, default_value = "80x25")]
-    dimensions: Window,
+    /// How -I/--invert-colors inverts: flip the lightness of every color while
+    /// keeping its hue (perceptual), or negate every RGB channel (rgb)
+    #[structopt(
+        long,
+        default_value = "perceptual",
+        possible_values = &["perceptual", "rgb"],
+        value_name = "MODE"
+    )]
+    invert_mode: InvertMode,
 
-    /// Launch in TUI mode
+    /// Rotate the hue of every color of the theme by this many degrees,
+    /// e.g. +15. This, --saturate, --lighten, --warmth and -I can be given
+    /// several times, and are applied in the order they're given.
+    #[structopt(
+        long,
+        allow_hyphen_values = true,
+        number_of_values = 1,
+        value_name = "DEGREES"
+    )]
+    hue: Vec<Hue>,
+
+    /// Make every color of the theme more or less colorful by this
+    /// percentage, e.g. -20%
+    #[structopt(
+        long,
+        allow_hyphen_values = true,
+        number_of_values = 1,
+        value_name = "PERCENT"
+    )]
+    saturate: Vec<Saturate>,
+
+    /// Lighten every color of the theme by this percentage, or darken it if
+    /// negative, e.g. 5%
+    #[structopt(
+        long,
+        allow_hyphen_values = true,
+        number_of_values = 1,
+        value_name = "PERCENT"
+    )]
+    lighten: Vec<Lighten>,
+
+    /// Make every color of the theme warmer, or cooler if negative, from -100
+    /// to 100
+    #[structopt(
+        long,
+        allow_hyphen_values = true,
+        number_of_values = 1,
+        value_name = "AMOUNT"
+    )]
+    warmth: Vec<Warmth>,
+
+    /// -I/--invert-colors, --hue, --saturate, --lighten and --warmth, in the
+    /// order they were given
+    #[structopt(skip)]
+    transforms: Vec<Box<dyn Transform>>,
+
+    /// Set a single color of the theme, e.g. colors.normal.red=#ff5555. Can
+    /// be given several times. Applies to the theme a command ends up with,
+    /// e.g. the derived one.
+    #[structopt(long = "set", number_of_values = 1, value_name = "KEY=COLOR")]
+    overrides: Vec<Override>,
+
+    /// Derive dim colors from the normal colors, as alacritty does, if the
+    /// theme doesn't define them
+    #[structopt(long)]
+    derive_dim: bool,
+
+    /// Lighten or darken the foreground and the normal and bright colors of
+    /// the theme, keeping their hue, until they have at least this contrast
+    /// ratio with the background, e.g. 4.5
+    #[structopt(long, value_name = "RATIO")]
+    ensure_contrast: Option<f64>,
+
+    /// Dimensions of window size in columns and lines, e.g. 80x25
+    #[structopt(long, short)]
+    dimensions: Option<Dimensions>,
+
+    /// Window position in pixels, e.g. 100x50
+    #[structopt(long, allow_hyphen_values = true)]
+    position: Option<Position>,
+
+    /// Window padding in pixels, e.g. 8x4
+    #[structopt(long)]
+    padding: Option<Padding>,
+
+    /// Spread additional padding evenly around the terminal content
+    #[structopt(long, value_name = "true|false")]
+    dynamic_padding: Option<bool>,
+
+    /// Window decorations: full, none, transparent or buttonless
+    #[structopt(long)]
+    decorations: Option<Decorations>,
+
+    /// Background opacity, between 0.0 and 1.0
+    #[structopt(long)]
+    opacity: Option<Opacity>,
+
+    /// Startup mode: windowed, maximized, fullscreen or simple-fullscreen
+    #[structopt(long)]
+    startup_mode: Option<StartupMode>,
+
+    /// Window title
+    #[structopt(long)]
+    title: Option<String>,
+
+    /// Allow terminal applications to change the window title
+    #[structopt(long, value_name = "true|false")]
+    dynamic_title: Option<bool>,
+
+    /// Window class: <instance> or <instance>,<general>
+    #[structopt(long)]
+    class: Option<Class>,
+
+    /// Config file to use instead of the one alacritty would load. Can also be
+    /// set with $ALACRITTY_CONF_CONFIG_FILE.
+    #[structopt(long, parse(from_os_str), value_name = "PATH")]
+    config_file: Option<PathBuf>,
+
+    /// Write the colors to this file instead, and import it from the config
+    #[structopt(long, parse(from_os_str), value_name = "PATH")]
+    colors_file: Option<PathBuf>,
+
+    /// Replace the config with one built only from the command line arguments
+    /// if the existing config can't be parsed
+    #[structopt(long)]
+    force: bool,
+
+    /// Print the new config instead of writing it
+    #[structopt(long, conflicts_with = "diff")]
+    dry_run: bool,
+
+    /// Print a diff between the current and the new config instead of writing
+    /// it. Exits with status 1 if they differ, and 2 if something went
+    /// wrong.
+    #[structopt(long)]
+    diff: bool,
+
+    /// Number of backups of previous configs to keep; 0 disables backups
+    #[structopt(long, default_value = "10", value_name = "N")]
+    keep_backups: usize,
+
+    /// Launch in TUI mode, showing the theme of the config before and after
+    /// the change, or the themes being compared, side by side
     #[structopt(long)]
     tui: bool,
+
+    #[structopt(subcommand)]
+    cmd: Option<Command>,
 }
 
-// App state
-struct App {
-    mode: bool,
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// List previous versions of the config, newest first
+    History,
+
+    /// Roll back to the config from N changes ago. Undoing again keeps
+    /// going back from there.
+    Undo {
+        #[structopt(default_value = "1")]
+        n: usize,
+    },
+
+    /// Restore a previous version of the config, by its id or number in
+    /// `history`
+    Restore { id: String },
+
+    /// Save, apply and list named bundles of theme, font and window size
+    Profile(ProfileCommand),
+
+    /// Use a light theme during the day and a dark one at night
+    Schedule {
+        /// Theme to use during the day
+        #[structopt(long, value_name = "THEME")]
+        light: String,
+
+        /// Theme to use at night
+        #[structopt(long, value_name = "THEME")]
+        dark: String,
+
+        /// When the day starts
+        #[structopt(
+            long,
+            default_value = "7:00",
+            value_name = "HH:MM",
+            parse(try_from_str = schedule::parse_time)
+        )]
+        day_start: NaiveTime,
+
+        /// When the day ends
+        #[structopt(
+            long,
+            default_value = "19:00",
+            value_name = "HH:MM",
+            parse(try_from_str = schedule::parse_time)
+        )]
+        day_end: NaiveTime,
+
+        /// Latitude in degrees north. Together with --longitude, the day lasts
+        /// from sunrise to sunset instead of --day-start to --day-end.
+        #[structopt(long, requires = "longitude", allow_hyphen_values = true)]
+        latitude: Option<f64>,
+
+        /// Longitude in degrees east
+        #[structopt(long, requires = "latitude", allow_hyphen_values = true)]
+        longitude: Option<f64>,
+
+        /// Keep running, and switch the theme whenever the time comes
+        #[structopt(long)]
+        daemon: bool,
+    },
+
+    /// Make a light theme out of a dark one, or the other way around, and
+    /// apply it
+    #[structopt(group = ArgGroup::with_name("period").required(true))]
+    Derive {
+        /// Theme to start from: a preset or a theme file
+        theme: String,
+
+        /// Derive a light theme
+        #[structopt(long, group = "period")]
+        light: bool,
+
+        /// Derive a dark theme
+        #[structopt(long, group = "period")]
+        dark: bool,
+
+        /// Save the theme to this file instead of applying it. The file can
+        /// be used as a theme afterwards.
+        #[structopt(long, parse(from_os_str), value_name = "PATH")]
+        save: Option<PathBuf>,
+    },
+
+    /// Mix two themes, and apply the result
+    Blend {
+        /// Theme to start from: a preset or a theme file
+        from: String,
+
+        /// Theme to mix in: a preset or a theme file
+        to: String,
+
+        /// How far to go from the first theme to the second, from 0 to 1.
+        /// Halfway by default.
+        #[structopt(long, conflicts_with = "steps", value_name = "RATIO")]
+        ratio: Option<f64>,
+
+        /// Make this many themes evenly spaced between the two instead, and
+        /// print them or save them as numbered files with --save
+        #[structopt(long, value_name = "N")]
+        steps: Option<NonZeroUsize>,
+
+        /// Save the theme to this file instead of applying it
+        #[structopt(long, parse(from_os_str), value_name = "PATH")]
+        save: Option<PathBuf>,
+    },
+
+    /// Make a theme from a single color or the colors of an image, and
+    /// apply it
+    #[structopt(group = ArgGroup::with_name("source").required(true))]
+    Generate {
+        /// Color to build the theme around, e.g. '#5e81ac'
+        #[structopt(
+            long,
+            group = "source",
+            parse(try_from_str = parse_color),
+            value_name = "COLOR"
+        )]
+        seed: Option<RGB8>,
+
+        /// PNG image, such as a wallpaper, to take the colors from
+        #[structopt(long, group = "source", parse(from_os_str), value_name = "PATH")]
+        from_image: Option<PathBuf>,
+
+        /// Make a light theme instead of a dark one
+        #[structopt(long)]
+        light: bool,
+
+        /// Save the theme to this file instead of applying it
+        #[structopt(long, parse(from_os_str), value_name = "PATH")]
+        save: Option<PathBuf>,
+    },
+
+    /// Check whether the colors of a theme are readable on its background.
+    /// Exits with status 1 if any is below the required level, and 2 if
+    /// something went wrong.
+    Check {
+        /// Theme to check: a preset or a theme file
+        theme: String,
+
+        /// WCAG level every color has to reach: aa (4.5:1) or aaa (7:1)
+        #[structopt(
+            long,
+            default_value = "aa",
+            possible_values = &["aa", "aaa"],
+            case_insensitive = true,
+            value_name = "LEVEL"
+        )]
+        level: Level,
+
+        /// Print the results as JSON
+        #[structopt(long)]
+        json: bool,
+    },
+
+    /// Show two themes side by side with how much every color differs (ΔE),
+    /// marking the largest differences. Use --tui for a split view.
+    Compare {
+        /// First theme: a preset or a theme file
+        a: String,
+
+        /// Second theme: a preset or a theme file
+        b: String,
+    },
+
+    /// Show how a theme looks with color vision deficiencies, and warn about
+    /// accent colors that become hard to tell apart
+    Cvd {
+        /// Theme to simulate: a preset or a theme file
+        theme: String,
+
+        /// Deficiency to simulate: protan, deutan or tritan. All of them by
+        /// default.
+        #[structopt(long = "type", value_name = "TYPE")]
+        deficiency: Option<Deficiency>,
+
+        /// Apply a variant of the theme adjusted for the deficiency. Requires
+        /// --type.
+        #[structopt(long, requires = "deficiency")]
+        adjust: bool,
+
+        /// Save the adjusted theme to this file instead of applying it
+        #[structopt(long, requires = "adjust", parse(from_os_str), value_name = "PATH")]
+        save: Option<PathBuf>,
+    },
+
+    /// Apply a theme file, and apply it again whenever it's saved
+    Watch {
+        #[structopt(parse(from_os_str))]
+        theme_file: PathBuf,
+    },
 }
 
-fn main() -> Result<(), io::Error> {
-    let args = Args::from_args();
+#[derive(Debug, StructOpt)]
+enum ProfileCommand {
+    /// Save the theme, font and window size of the current config as a
+    /// profile
+    Save { name: String },
+
+    /// Apply a profile to the config
+    Apply { name: String },
+
+    /// List the saved profiles
+    List,
+}
+
+/// Exit status when something went wrong. Like diff(1), distinct from the
+/// statuses that report a result.
+const EXIT_ERROR: i32 = 2;
+/// Exit status of `--diff` when the config would change.
+const EXIT_CHANGED: i32 = 1;
+/// Exit status of `check` when a color is below the required level. An
+/// error exits with `EXIT_ERROR` instead, so that it isn't mistaken for an
+/// unreadable theme.
+const EXIT_UNREADABLE: i32 = 1;
+
+fn main() {
+    let matches = Args::clap().get_matches_safe().unwrap_or_else(|e| {
+        // clap exits with status 1 on usage errors
+        if e.use_stderr() {
+            eprintln!("{}", e.message);
+            process::exit(EXIT_ERROR);
+        }
+        e.exit()
+    });
+    let mut args = Args::from_clap(&matches);
+    args.transforms = transforms(&matches, &args);
+
+    match run(args) {
+        Ok(0) => {}
+        Ok(status) => process::exit(status),
+        Err(e) => {
+            let mut tc_stderr = StandardStream::stderr(ColorChoice::Always);
 
-    // termcolor stdout/stderr
+            // if stderr is gone there is nowhere left to report the error
+            let _ = e.report(&mut tc_stderr);
+
+            process::exit(EXIT_ERROR);
+        }
+    }
+}
+
+/// The transforms in `args`, in the order they were given on the command
+/// line, which is only known to `matches`.
+fn transforms(matches: &ArgMatches, args: &Args) -> Vec<Box<dyn Transform>> {
+    fn indexed<'a, T: Transform + Copy + 'static>(
+        matches: &'a ArgMatches,
+        name: &str,
+        values: &'a [T],
+    ) -> impl Iterator<Item = (usize, Box<dyn Transform>)> + 'a {
+        matches
+            .indices_of(name)
+            .into_iter()
+            .flatten()
+            .zip(values)
+            .map(|(index, &value)| (index, Box::new(value) as Box<dyn Transform>))
+    }
+
+    let mut transforms: Vec<_> = indexed(matches, "hue", &args.hue)
+        .chain(indexed(matches, "saturate", &args.saturate))
+        .chain(indexed(matches, "lighten", &args.lighten))
+        .chain(indexed(matches, "warmth", &args.warmth))
+        .collect();
+    if args.invert_colors {
+        let index = matches.index_of("invert-colors").unwrap_or_default();
+        transforms.push((index, Box::new(Invert(args.invert_mode))));
+    }
+    transforms.sort_by_key(|&(index, _)| index);
+
+    transforms
+        .into_iter()
+        .map(|(_, transform)| transform)
+        .collect()
+}
+
+/// Run the command given by `args`, returning the exit status.
+fn run(args: Args) -> Result<i32> {
+    // termcolor stdout
     let mut tc_stdout = StandardStream::stdout(ColorChoice::Always);
-    let mut tc_stderr = StandardStream::stderr(ColorChoice::Always);
 
     if args.list_themes {
-        writeln!(
-            tc_stdout,
-            "\
-afterglow
-argonaut
-ayu-dark
-ayu-mirage
-base16
-breeze
-dracula
-kitty
-gruvbox
-material
-monokai-soda"
-        )?;
+        for theme in &ColorTheme::ALL {
+            writeln!(tc_stdout, "{}", theme)?;
+        }
 
         tc_stdout.flush()?;
 
-        return Ok(());
+        return Ok(0);
+    }
+
+    let config_path = discover::locate(args.config_file.as_deref())?;
+    // the config changes are written to, and the one history is kept for
+    let path = config_path.target()?;
+
+    match args.cmd {
+        Some(Command::History) => {
+            history::print(&path, &mut tc_stdout)?;
+            return Ok(0);
+        }
+        Some(Command::Undo { n }) => {
+            let backup = history::undo(&path, n, args.keep_backups)?;
+            print_restored(&mut tc_stdout, &backup)?;
+            return Ok(0);
+        }
+        Some(Command::Restore { ref id }) => {
+            let backup = history::restore(&path, id, args.keep_backups)?;
+            print_restored(&mut tc_stdout, &backup)?;
+            return Ok(0);
+        }
+        Some(Command::Profile(ProfileCommand::Save { ref name })) => {
+            // the profile holds what alacritty uses, imports and all
+            let (_, _, config) = read_config(&config_path, false)?;
+            let existed = profile::save(name, &config)?;
+
+            writeln!(
+                tc_stdout,
+                "{} profile '{}': {}",
+                if existed { "Updated" } else { "Saved" },
+                name,
+                history::summary(&config)
+            )?;
+            tc_stdout.flush()?;
+            return Ok(0);
+        }
+        Some(Command::Profile(ProfileCommand::Apply { ref name })) => {
+            let profile = profile::load(name)?;
+            let (old_source, config, _) = read_config(&config_path, args.force)?;
+
+            return write_config(
+                &mut tc_stdout,
+                &config_path,
+                old_source.as_deref(),
+                config::merge(profile, config),
+                &args,
+                &mut HashSet::new(),
+            );
+        }
+        Some(Command::Profile(ProfileCommand::List)) => {
+            profile::print(&mut tc_stdout)?;
+            return Ok(0);
+        }
+        Some(Command::Schedule {
+            ref light,
+            ref dark,
+            day_start,
+            day_end,
+            latitude,
+            longitude,
+            daemon,
+        }) => {
+            let schedule = match latitude.zip(longitude) {
+                Some((latitude, longitude)) => {
+                    if !(-90.0..=90.0).contains(&latitude) {
+                        return Err(Error::out_of_range("latitude", latitude, -90, 90));
+                    }
+                    if !(-180.0..=180.0).contains(&longitude) {
+                        return Err(Error::out_of_range("longitude", longitude, -180, 180));
+                    }
+                    Schedule::Sun {
+                        latitude,
+                        longitude,
+                    }
+                }
+                None => Schedule::Hours {
+                    start: day_start,
+                    end: day_end,
+                },
+            };
+
+            return run_schedule(
+                &mut tc_stdout,
+                &config_path,
+                &args,
+                schedule,
+                (light, dark),
+                daemon,
+            );
+        }
+        Some(Command::Derive {
+            ref theme,
+            light,
+            dark,
+            ref save,
+        }) => {
+            // exactly one of them is set
+            let period = match (light, dark) {
+                (true, _) => Period::Light,
+                (_, true) => Period::Dark,
+                _ => unreachable!(),
+            };
+            return run_derive(
+                &mut tc_stdout,
+                &config_path,
+                &args,
+                theme,
+                period,
+                save.as_deref(),
+            );
+        }
+        Some(Command::Blend {
+            ref from,
+            ref to,
+            ratio,
+            steps,
+            ref save,
+        }) => {
+            return run_blend(
+                &mut tc_stdout,
+                &config_path,
+                &args,
+                (from, to),
+                ratio.unwrap_or(0.5),
+                steps,
+                save.as_deref(),
+            );
+        }
+        Some(Command::Generate {
+            seed,
+            ref from_image,
+            light,
+            ref save,
+        }) => {
+            let period = if light { Period::Light } else { Period::Dark };
+            // exactly one of them is set
+            let (mut theme, description) = match (seed, from_image) {
+                (Some(seed), _) => (
+                    generate::from_seed(seed, period),
+                    format!("the theme generated from {}", seed.to_hex()),
+                ),
+                (_, Some(path)) => (
+                    generate::from_image(path, period)?,
+                    format!("the theme generated from {}", path.display()),
+                ),
+                _ => unreachable!(),
+            };
+            theme_options(&mut theme, &args);
+            finish_theme(&mut tc_stdout, &mut theme, &args)?;
+
+            return emit_theme(
+                &mut tc_stdout,
+                &config_path,
+                &args,
+                theme,
+                save.as_deref(),
+                &description,
+            );
+        }
+        Some(Command::Check {
+            ref theme,
+            level,
+            json,
+        }) => {
+            let checked = if json {
+                // keep the report of --ensure-contrast out of the JSON
+                let mut tc_stderr = StandardStream::stderr(ColorChoice::Always);
+                build_theme(&mut tc_stderr, theme, &args)?
+            } else {
+                build_theme(&mut tc_stdout, theme, &args)?
+            };
+            let entries = check::check(&checked);
+
+            if json {
+                check::print_json(&mut io::stdout(), theme, &checked, &entries, level)?;
+            } else {
+                check::print_table(&mut tc_stdout, theme, &checked, &entries, level)?;
+            }
+
+            let flagged = entries.iter().any(|entry| entry.flagged(level));
+            return Ok(if flagged { EXIT_UNREADABLE } else { 0 });
+        }
+        Some(Command::Compare { ref a, ref b }) => {
+            return run_compare(&mut tc_stdout, &args, (a, b));
+        }
+        Some(Command::Cvd {
+            ref theme,
+            deficiency,
+            adjust,
+            ref save,
+        }) => {
+            let mut simulated = read_theme(theme, &args)?;
+            // an adjusted theme is finished after adjusting instead
+            if !adjust {
+                finish_theme(&mut tc_stdout, &mut simulated, &args)?;
+            }
+
+            let deficiency = match deficiency {
+                Some(deficiency) if adjust => deficiency,
+                Some(deficiency) => {
+                    cvd::print(&mut tc_stdout, &simulated, deficiency)?;
+                    return Ok(0);
+                }
+                None => {
+                    for (i, &deficiency) in Deficiency::ALL.iter().enumerate() {
+                        if i > 0 {
+                            writeln!(tc_stdout)?;
+                        }
+                        cvd::print(&mut tc_stdout, &simulated, deficiency)?;
+                    }
+                    return Ok(0);
+                }
+            };
+
+            // show what's left to confuse after adjusting
+            let mut adjusted = cvd::adjust(&simulated, deficiency);
+            finish_theme(&mut tc_stdout, &mut adjusted, &args)?;
+            cvd::print(&mut tc_stdout, &adjusted, deficiency)?;
+            writeln!(tc_stdout)?;
+
+            let description = format!("the {}-adjusted variant of {}", deficiency, theme);
+            return emit_theme(
+                &mut tc_stdout,
+                &config_path,
+                &args,
+                adjusted,
+                save.as_deref(),
+                &description,
+            );
+        }
+        Some(Command::Watch { ref theme_file }) => {
+            return run_watch(&mut tc_stdout, &config_path, &args, theme_file);
+        }
+        None => {}
     }
 
     // TODO do not error out if none is specified, just get the default one instead
     // and eventually correctly parse existing themes
-    let theme = if let Some(theme_preset) = args.theme {
-        let mut theme = Theme::from(theme_preset);
+    let theme = match args.theme {
+        Some(ref theme_preset) => build_theme(&mut tc_stdout, theme_preset, &args)?,
+        None => return Err(ErrorKind::MissingTheme.into()),
+    };
+
+    let (old_config, config, current) = read_config(&config_path, args.force)?;
+
+    // TODO validate font
+
+    // build the new config before writing anything, so that an invalid
+    // argument doesn't leave behind a broken config
+    let new_theme = theme.clone();
+    let new_config = config::merge(args_config(&args, theme, &current)?, config);
+
+    let status = write_config(
+        &mut tc_stdout,
+        &config_path,
+        old_config.as_deref(),
+        new_config,
+        &args,
+        &mut HashSet::new(),
+    )?;
+
+    if args.tui {
+        let name = args.theme.as_deref().unwrap_or("after");
+        run_tui(
+            current
+                .theme()
+                .map(|old_theme| (("before", name), (old_theme, &new_theme))),
+        )?;
+    }
+
+    Ok(status)
+}
+
+/// Themes shown side by side in the TUI, with their names.
+type Comparison<'a> = ((&'a str, &'a str), (&'a Theme, &'a Theme));
+
+/// Run the TUI until `q` is pressed. With `comparison`, the two themes are
+/// shown side by side, along with how much every color differs, and can be
+/// scrolled with j/k or the arrow keys.
+fn run_tui(comparison: Option<Comparison>) -> Result<()> {
+    let differences = match comparison {
+        Some((_, themes)) => compare::compare(themes.0, themes.1),
+        None => Vec::new(),
+    };
+
+    let stdout = io::stdout().into_raw_mode()?;
+    let backend = TermionBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+    terminal.clear()?;
+
+    let events = Events::new();
+    let mut scroll: u16 = 0;
+
+    loop {
+        terminal.draw(|f| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .margin(2)
+                .constraints(
+                    [
+                        Constraint::Percentage(10),
+                        Constraint::Percentage(80),
+                        Constraint::Percentage(10),
+                    ]
+                    .as_ref(),
+                )
+                .split(f.size());
+
+            let block = Block::default().title("Color themes").borders(Borders::ALL);
+            f.render_widget(block, chunks[0]);
+
+            if let Some(comparison) = comparison {
+                compare::draw(f, chunks[1], comparison, &differences, scroll);
+            }
+        })?;
+
+        match events.next() {
+            Ok(Event::Input(Key::Char('q'))) | Err(_) => break,
+            Ok(Event::Input(Key::Down)) | Ok(Event::Input(Key::Char('j'))) => {
+                scroll = (scroll + 1).min(differences.len().saturating_sub(1) as u16);
+            }
+            Ok(Event::Input(Key::Up)) | Ok(Event::Input(Key::Char('k'))) => {
+                scroll = scroll.saturating_sub(1);
+            }
+            _ => {}
+        }
+    }
+    terminal.clear()?;
+
+    Ok(())
+}
+
+/// Load the preset `name`, or else the theme file at that path, with the
+/// theme options in `args` applied.
+fn build_theme<W: WriteColor>(out: &mut W, name: &str, args: &Args) -> Result<Theme> {
+    let mut theme = read_theme(name, args)?;
+    finish_theme(out, &mut theme, args)?;
+    Ok(theme)
+}
+
+/// Load the preset `name`, or else the theme file at that path, with the
+/// transforms in `args` applied. The other theme options are for the theme
+/// a command makes out of it, see `finish_theme`.
+fn read_theme(name: &str, args: &Args) -> Result<Theme> {
+    let mut theme = match name.parse::<ColorTheme>() {
+        Ok(preset) => Theme::from(preset),
+        Err(_) if Path::new(name).is_file() => config::load_theme(Path::new(name))?,
+        Err(e) => return Err(e.into()),
+    };
 
-        if args.invert_colors {
-            theme.invert();
+    theme_options(&mut theme, args);
+    Ok(theme)
+}
+
+/// Apply the transforms in `args`, in the order they were given.
+fn theme_options(theme: &mut Theme, args: &Args) {
+    for transform in &args.transforms {
+        transform.apply(theme);
+    }
+}
+
+/// Apply the options in `args` that are meant for the theme a command ends
+/// up with, reporting the changes made by `--ensure-contrast` to `out`.
+fn finish_theme<W: WriteColor>(out: &mut W, theme: &mut Theme, args: &Args) -> Result<()> {
+    // after everything else, so that the colors are exactly as given
+    for color in &args.overrides {
+        color.apply(theme);
+    }
+
+    if args.derive_dim && theme.dim.is_none() {
+        theme.dim = Some(theme.normal.dimmed());
+    }
+
+    // last, since everything else may change the background
+    if let Some(ratio) = args.ensure_contrast {
+        if !(1.0..=21.0).contains(&ratio) {
+            return Err(Error::out_of_range("--ensure-contrast", ratio, 1, 21));
         }
 
-        theme
+        let adjustments = check::ensure_contrast(theme, ratio);
+        check::print_adjustments(out, &adjustments, ratio)?;
+    }
+
+    Ok(())
+}
+
+/// Replace the theme of the config, leaving everything else as it is. See
+/// `write_config` for `written`.
+fn apply_theme<W: WriteColor>(
+    out: &mut W,
+    config_path: &ConfigPath,
+    args: &Args,
+    theme: Theme,
+    written: &mut HashSet<PathBuf>,
+) -> Result<i32> {
+    let (old_source, config, _) = read_config(config_path, args.force)?;
+
+    write_config(
+        out,
+        config_path,
+        old_source.as_deref(),
+        config::merge(PartialConfig::new(None, Some(theme), None), config),
+        args,
+        written,
+    )
+}
+
+/// Apply the light or dark theme, whichever `schedule` says to use now. With
+/// `daemon`, keep doing so at every transition.
+fn run_schedule<W: WriteColor>(
+    out: &mut W,
+    config_path: &ConfigPath,
+    args: &Args,
+    schedule: Schedule,
+    (light, dark): (&str, &str),
+    daemon: bool,
+) -> Result<i32> {
+    // catch unknown themes before waiting for a transition
+    let themes = (
+        build_theme(out, light, args)?,
+        build_theme(out, dark, args)?,
+    );
+
+    loop {
+        let now = Local::now();
+        let period = schedule.period_at(now);
+        let (name, theme) = match period {
+            Period::Light => (light, themes.0.clone()),
+            Period::Dark => (dark, themes.1.clone()),
+        };
+
+        let result = apply_theme(out, config_path, args, theme, &mut HashSet::new());
+
+        let next = schedule.next_transition(now);
+        writeln!(
+            out,
+            "Using the {} theme ({}){}",
+            period,
+            name,
+            match next {
+                Some((time, _)) => format!(" until {}", time.format("%Y-%m-%d %H:%M")),
+                None => String::new(),
+            }
+        )?;
+        out.flush()?;
+
+        if !daemon {
+            return result;
+        }
+        // the config may just be mid-edit, so keep going and try again at the
+        // next transition
+        if let Err(e) = result {
+            e.report(&mut StandardStream::stderr(ColorChoice::Always))?;
+        }
+
+        match next {
+            Some((time, _)) => {
+                // sleep in steps, since the clock may jump, e.g. after a
+                // suspend
+                while Local::now() < time {
+                    let left = (time - Local::now()).to_std().unwrap_or_default();
+                    thread::sleep(left.min(Duration::from_secs(600)));
+                }
+            }
+            None => thread::sleep(Duration::from_secs(24 * 60 * 60)),
+        }
+    }
+}
+
+/// Derive the `period` variant of the theme `name`, and apply it or save it
+/// to `save`.
+fn run_derive<W: WriteColor>(
+    out: &mut W,
+    config_path: &ConfigPath,
+    args: &Args,
+    name: &str,
+    period: Period,
+    save: Option<&Path>,
+) -> Result<i32> {
+    let theme = read_theme(name, args)?;
+    if derive::period(&theme) == period {
+        out.set_color(ColorSpec::new().set_fg(Some(Color::Green)).set_bold(true))?;
+        write!(out, "note:")?;
+        out.reset()?;
+        writeln!(
+            out,
+            " {} already is a {} theme, so it's used as it is",
+            name, period
+        )?;
+    }
+    let mut theme = derive::variant(&theme, period);
+    finish_theme(out, &mut theme, args)?;
+
+    let description = format!("the {} variant of {}", period, name);
+    emit_theme(out, config_path, args, theme, save, &description)
+}
+
+/// Compare the themes `a` and `b`, printing a table or, with `--tui`,
+/// showing them side by side in the TUI.
+fn run_compare<W: WriteColor>(out: &mut W, args: &Args, names: (&str, &str)) -> Result<i32> {
+    let themes = (
+        build_theme(out, names.0, args)?,
+        build_theme(out, names.1, args)?,
+    );
+
+    if args.tui {
+        run_tui(Some((names, (&themes.0, &themes.1))))?;
     } else {
-        tc_stderr.set_color(ColorSpec::new().set_fg(Some(Color::Red)).set_bold(true))?;
-        write!(tc_stderr, "error:")?;
-        tc_stderr.reset()?;
-        write!(tc_stderr, " You must provide a value for '")?;
-        tc_stderr.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)))?;
-        write!(tc_stderr, "<theme>")?;
-        tc_stderr.reset()?;
-
-        write!(tc_stderr, "'\nUse ")?;
-        tc_stderr.set_color(ColorSpec::new().set_fg(Some(Color::Green)))?;
-        write!(tc_stderr, "-l")?;
-        tc_stderr.reset()?;
-        write!(tc_stderr, "/")?;
-        tc_stderr.set_color(ColorSpec::new().set_fg(Some(Color::Green)))?;
-        write!(tc_stderr, "--list-themes")?;
-        tc_stderr.reset()?;
-        writeln!(tc_stderr, " to view the available themes.")?;
-
-        tc_stderr.flush()?;
-
-        return Ok(());
+        compare::print(out, names, &compare::compare(&themes.0, &themes.1))?;
+    }
+
+    Ok(0)
+}
+
+/// Blend the themes `from` and `to` by `ratio` and apply the result or save
+/// it to `save`. With `steps`, print or save that many themes in between
+/// instead.
+fn run_blend<W: WriteColor>(
+    out: &mut W,
+    config_path: &ConfigPath,
+    args: &Args,
+    (from, to): (&str, &str),
+    ratio: f64,
+    steps: Option<NonZeroUsize>,
+    save: Option<&Path>,
+) -> Result<i32> {
+    let themes = (read_theme(from, args)?, read_theme(to, args)?);
+
+    let steps = match steps {
+        Some(steps) => steps.get(),
+        None => {
+            if !(0.0..=1.0).contains(&ratio) {
+                return Err(Error::out_of_range("--ratio", ratio, 0, 1));
+            }
+
+            let mut theme = blend::blend(&themes.0, &themes.1, ratio);
+            finish_theme(out, &mut theme, args)?;
+
+            let description = format!("{} blended {} into {}", from, ratio, to);
+            return emit_theme(out, config_path, args, theme, save, &description);
+        }
     };
 
-    let path = {
-        let mut path = dirs::config_dir().unwrap();
-        path.push("alacritty");
-        path.push("alacritty.yml");
-        path
+    for step in 1..=steps {
+        let ratio = step as f64 / (steps + 1) as f64;
+        let mut theme = blend::blend(&themes.0, &themes.1, ratio);
+        finish_theme(out, &mut theme, args)?;
+
+        match save {
+            Some(path) => {
+                let path = numbered(path, step);
+                let description = format!("step {} of {} ({:.3})", step, steps, ratio);
+                emit_theme(out, config_path, args, theme, Some(&path), &description)?;
+            }
+            None => {
+                writeln!(out, "# step {} of {} ({:.3})", step, steps, ratio)?;
+                writeln!(out, "{}\n", theme)?;
+            }
+        }
+    }
+    out.flush()?;
+
+    Ok(0)
+}
+
+/// `path` with `-n` added to its file name, before the extension.
+fn numbered(path: &Path, n: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!("{}-{}.{}", stem, n, extension.to_string_lossy()),
+        None => format!("{}-{}", stem, n),
     };
 
-    // TODO: correct error handling
-    let old_config = fs::read_to_string(&path)?;
+    path.with_file_name(name)
+}
 
-    let mut new_config = File::create(&path)?;
+/// Apply `theme`, or save it to `save` as a theme file, reporting it as
+/// `description`.
+fn emit_theme<W: WriteColor>(
+    out: &mut W,
+    config_path: &ConfigPath,
+    args: &Args,
+    theme: Theme,
+    save: Option<&Path>,
+    description: &str,
+) -> Result<i32> {
+    let path = match save {
+        Some(path) => path,
+        None => return apply_theme(out, config_path, args, theme, &mut HashSet::new()),
+    };
 
-    // TODO detect if the file does not already exist, and inform the user that
-    // since it's the first time that they're creating the config, changes only
-    // apply after restatring alacritty
+    let contents = format!(
+        "{}\n\n{}\n",
+        history::header(),
+        config::render_theme(&theme, Format::of(path))
+    );
+    if args.dry_run {
+        writeln!(out, "{}", contents)?;
+    } else {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(|e| Error::from(e).in_file(dir))?;
+        }
+        backup::write(path, &contents, args.keep_backups)?;
+        writeln!(out, "Saved {} to {}", description, path.display())?;
+    }
+    out.flush()?;
 
-    // TODO validate font
+    Ok(0)
+}
 
-    write!(
-        new_config,
-        "# generated by alacritty-conf ({})\n\n{}",
-        chrono::Utc::now().to_rfc2822(),
-        {
-            let old_config = config::parse(old_config.as_str());
-
-            match old_config {
-                Some(config) => config::merge(
-                    PartialConfig::new(
-                        match args.font {
-                            Some(f) => Some(Font {
-                                family: Some(f),
-                                size: FontSize(
-                                    args.font_size
-                                        .unwrap_or_else(|| config.font_size_or_default()),
-                                ),
-                            }),
-                            None => Some(Font {
-                                family: match config.font() {
-                                    // TODO make font family generic to avoid this mess
-                                    Some(f) => f.family.as_ref().map(|f| f.as_str().to_owned()),
-                                    None => None,
-                                },
-                                size: FontSize(
-                                    args.font_size
-                                        .unwrap_or_else(|| config.font_size_or_default()),
-                                ),
-                            }),
-                        },
-                        // TODO maybe these don't need to be wrapped in Option?
-                        Some(theme),
-                        Some(args.dimensions),
-                    ),
-                    config,
-                ),
-                // TODO isn't this problematic?
-                // if there isn't a config, or it couldn't be parsed,
-                // it should just be overwritten with CLI args right?
-                None => Config::default(),
+/// Apply the theme file at `path` whenever it's saved. Errors, such as a
+/// half-written theme, are reported without stopping.
+fn run_watch<W: WriteColor>(
+    out: &mut W,
+    config_path: &ConfigPath,
+    args: &Args,
+    path: &Path,
+) -> Result<i32> {
+    let name = path.to_string_lossy();
+    let mut watcher = Watcher::new(path)?;
+    // only the versions from before watching are backed up
+    let mut written = HashSet::new();
+
+    loop {
+        // a theme file always has to be read, even if a preset has its name
+        let result = config::load_theme(path).and_then(|mut theme| {
+            theme_options(&mut theme, args);
+            finish_theme(out, &mut theme, args)?;
+            apply_theme(out, config_path, args, theme, &mut written)
+        });
+
+        match result {
+            Ok(_) => writeln!(out, "Applied {}, waiting for changes", name)?,
+            Err(e) => e.report(&mut StandardStream::stderr(ColorChoice::Always))?,
+        }
+        out.flush()?;
+
+        watcher.wait()?;
+    }
+}
+
+/// A config as written, the same config with its imports merged in, and its
+/// source, if it exists.
+type ReadConfig = (Option<String>, PartialConfig, PartialConfig);
+
+/// Read and parse the config at `config_path`, returning its source, the
+/// config as written, which changes are made to, and the config with its
+/// imports merged in, which is what alacritty uses. A config that can't be
+/// parsed is an error, unless `force` is set, in which case it's treated as
+/// empty.
+fn read_config(config_path: &ConfigPath, force: bool) -> Result<ReadConfig> {
+    // a missing config is fine, it just means this is the first time that
+    // alacritty-conf (or alacritty) is being configured
+    let source = match fs::read_to_string(&config_path.path) {
+        Ok(source) => source,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return Ok((None, PartialConfig::default(), PartialConfig::default()))
+        }
+        Err(e) => return Err(Error::from(e).in_file(&config_path.path)),
+    };
+
+    // parse before anything is written, so that a broken config is never
+    // replaced by accident
+    let (config, merged) = match config::parse_own_and_merged(&config_path.path, &source) {
+        Ok(configs) => configs,
+        Err(_) if force => (PartialConfig::default(), PartialConfig::default()),
+        Err(e) => {
+            return Err(e.in_file(&config_path.path).with_hint(
+                "The config was left untouched. Fix the error, or use --force to replace it \
+                 with one built only from the command line arguments.",
+            ))
+        }
+    };
+
+    Ok((Some(source), config, merged))
+}
+
+/// A file about to be written.
+struct Output {
+    path: PathBuf,
+    /// Its current contents, if it exists.
+    old_source: Option<String>,
+    contents: String,
+}
+
+/// Write `config` over the config at `config_path`, whose contents are
+/// `old_source`, or only show it with `--dry-run` and `--diff`. With
+/// `--colors-file`, the colors are written to that file instead and it's
+/// imported by the config. Returns the exit status.
+///
+/// Files in `written` aren't backed up again, and every file written is added
+/// to it. A command that keeps rewriting the same files, such as watch, thus
+/// only backs up the versions from before it started, rather than rotating
+/// them out with its own output.
+fn write_config<W: WriteColor>(
+    out: &mut W,
+    config_path: &ConfigPath,
+    old_source: Option<&str>,
+    mut config: Config,
+    args: &Args,
+    written: &mut HashSet<PathBuf>,
+) -> Result<i32> {
+    let path = config_path.target()?;
+
+    let mut outputs = Vec::new();
+    // without a theme, the colors file is left as it is
+    if let (Some(colors_path), Some(theme)) = (&args.colors_file, config.theme()) {
+        let colors_path = env::current_dir()?.join(colors_path);
+
+        outputs.push(Output {
+            old_source: match fs::read_to_string(&colors_path) {
+                Ok(source) => Some(source),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+                Err(e) => return Err(Error::from(e).in_file(&colors_path)),
+            },
+            contents: format!(
+                "{}\n\n{}\n",
+                history::header(),
+                config::render_theme(theme, Format::of(&colors_path))
+            ),
+            path: colors_path.clone(),
+        });
+        config.add_import(&colors_path, &path);
+    }
+    // the config itself comes first
+    outputs.insert(
+        0,
+        Output {
+            contents: format!(
+                "{}\n\n{}\n",
+                history::header(),
+                config::render(&config, Format::of(&path), args.colors_file.is_none())
+            ),
+            old_source: old_source.map(str::to_owned),
+            path,
+        },
+    );
+
+    if args.dry_run {
+        for output in &outputs {
+            if outputs.len() > 1 {
+                writeln!(out, "==> {} <==", output.path.display())?;
             }
+            writeln!(out, "{}", output.contents)?;
         }
-    )?;
+        out.flush()?;
+        return Ok(0);
+    }
 
-    if args.tui {
-        let stdout = io::stdout().into_raw_mode()?;
-        let backend = TermionBackend::new(stdout);
-        let mut terminal = Terminal::new(backend)?;
+    if args.diff {
+        let mut changed = false;
+        for (i, output) in outputs.iter().enumerate() {
+            // the config may be read from a system-wide file but written to a
+            // new one
+            let old_path = if i == 0 {
+                &config_path.path
+            } else {
+                &output.path
+            };
 
-        let mut events = Events::new();
+            // the header holds the current date, so it always differs
+            changed |= diff::print(
+                out,
+                &old_path.display().to_string(),
+                &output.path.display().to_string(),
+                history::strip_header(output.old_source.as_deref().unwrap_or("")),
+                history::strip_header(&output.contents),
+            )?;
+        }
+        return Ok(if changed { EXIT_CHANGED } else { 0 });
+    }
 
-        loop {
-            terminal.draw(|f| {
-                let chunks = Layout::default()
-                    .direction(Direction::Vertical)
-                    .margin(2)
-                    .constraints(
-                        [
-                            Constraint::Percentage(10),
-                            Constraint::Percentage(80),
-                            Constraint::Percentage(10),
-                        ]
-                        .as_ref(),
-                    )
-                    .split(f.size());
+    for (i, output) in outputs.iter().enumerate() {
+        let unchanged = output.old_source.as_deref().is_some_and(|old| {
+            history::strip_header(old) == history::strip_header(&output.contents)
+        });
+        // don't clutter the history with identical versions
+        if unchanged {
+            writeln!(out, "{} is up to date", output.path.display())?;
+            continue;
+        }
 
-                let block = Block::default().title("Color themes").borders(Borders::ALL);
-                f.render_widget(block, chunks[0]);
-            })?;
+        let created = !output.path.exists();
+        if created {
+            if let Some(dir) = output.path.parent() {
+                fs::create_dir_all(dir).map_err(|e| Error::from(e).in_file(dir))?;
+            }
+        }
+
+        let keep = if written.contains(&output.path) {
+            0
+        } else {
+            args.keep_backups
+        };
+        backup::write(&output.path, &output.contents, keep)?;
+        written.insert(output.path.clone());
+
+        match (i, created) {
+            (0, true) => {
+                out.set_color(ColorSpec::new().set_fg(Some(Color::Green)).set_bold(true))?;
+                write!(out, "note:")?;
+                out.reset()?;
+                write!(out, " created a new config at {}", output.path.display())?;
+                if config_path.origin == Origin::System {
+                    write!(out, ", based on {}", config_path)?;
+                }
+                writeln!(
+                    out,
+                    "\nSince alacritty wasn't configured before, changes only apply after \
+                     restarting it."
+                )?;
+            }
+            (0, false) => writeln!(out, "Updated {}", config_path)?,
+            (_, true) => writeln!(out, "Created {}", output.path.display())?,
+            (_, false) => writeln!(out, "Updated {}", output.path.display())?,
         }
     }
+    out.flush()?;
+
+    Ok(0)
+}
+
+fn print_restored<W: WriteColor>(out: &mut W, backup: &Backup) -> Result<()> {
+    write!(out, "Restored the config from ")?;
+    out.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)))?;
+    write!(out, "{}", backup.id)?;
+    out.reset()?;
+    writeln!(
+        out,
+        ", replaced at {}.",
+        backup.taken.format("%Y-%m-%d %H:%M:%S")
+    )?;
+    out.flush()?;
 
     Ok(())
 }
+
+/// Build the parts of the config that were given on the command line.
+/// Relative values such as `-s +2` are resolved against `current`, the
+/// config alacritty uses.
+fn args_config(args: &Args, theme: Theme, current: &PartialConfig) -> Result<PartialConfig> {
+    let font = if args.font.is_some() || args.font_size.is_some() {
+        // a relative size is resolved against the merged config, but the
+        // size itself is only written if one was given, so that an imported
+        // size keeps applying
+        let size = match args.font_size {
+            Some(size) => Some(size.apply(current.font_size_or_default())?),
+            None => None,
+        };
+
+        Some(Font {
+            family: args.font.clone(),
+            size,
+        })
+    } else {
+        None
+    };
+
+    let window = Window {
+        dimensions: args.dimensions,
+        position: args.position,
+        padding: args.padding,
+        dynamic_padding: args.dynamic_padding,
+        decorations: args.decorations,
+        opacity: args.opacity,
+        startup_mode: args.startup_mode,
+        title: args.title.clone(),
+        dynamic_title: args.dynamic_title,
+        class: args.class.clone(),
+    };
+
+    Ok(PartialConfig::new(
+        font,
+        Some(theme),
+        if window.is_empty() {
+            None
+        } else {
+            Some(window)
+        },
+    ))
+}
@@ -0,0 +1,109 @@
+use crate::backup;
+use crate::config::{self, Format, PartialConfig};
+use crate::discover;
+use crate::error::{Error, ErrorKind, Result};
+use crate::history;
+use crate::theme::Window;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use termcolor::{Color, ColorSpec, WriteColor};
+
+/// Directory that profiles are kept in, one config file each.
+pub fn dir() -> Result<PathBuf> {
+    let mut dir = discover::config_home()?;
+    dir.push("alacritty-conf");
+    dir.push("profiles");
+    Ok(dir)
+}
+
+fn path(name: &str) -> Result<PathBuf> {
+    // the name becomes a file name, so it can't leave the profile directory
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(ErrorKind::InvalidProfileName(name.to_owned()).into());
+    }
+
+    Ok(dir()?.join(format!("{}.yml", name)))
+}
+
+/// Save the theme, font and window size of `config` as the profile `name`,
+/// replacing it if it exists. Returns whether it existed.
+pub fn save(name: &str, config: &PartialConfig) -> Result<bool> {
+    let path = path(name)?;
+    let dir = dir()?;
+    fs::create_dir_all(&dir).map_err(|e| Error::from(e).in_file(&dir))?;
+
+    let profile = PartialConfig::new(
+        config.font().cloned(),
+        config.theme().cloned(),
+        config
+            .window()
+            .and_then(|window| window.dimensions)
+            .map(|dimensions| Window {
+                dimensions: Some(dimensions),
+                ..Window::default()
+            }),
+    );
+
+    let existed = path.exists();
+    // profiles are cheap to recreate, so they aren't backed up
+    backup::write(&path, &format!("{}\n", profile), 0)?;
+
+    Ok(existed)
+}
+
+/// Load the profile `name`, to be merged into a config.
+pub fn load(name: &str) -> Result<PartialConfig> {
+    let path = path(name)?;
+
+    let source = match fs::read_to_string(&path) {
+        Ok(source) => source,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return Err(ErrorKind::UnknownProfile(name.to_owned()).into())
+        }
+        Err(e) => return Err(Error::from(e).in_file(&path)),
+    };
+
+    config::parse(&source, Format::Yaml).map_err(|e| e.in_file(&path))
+}
+
+/// Print every profile along with a summary of it.
+pub fn print<W: WriteColor>(out: &mut W) -> Result<()> {
+    let dir = dir()?;
+
+    let mut names: Vec<String> = match fs::read_dir(&dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name();
+                Some(name.to_str()?.strip_suffix(".yml")?.to_owned())
+            })
+            .collect(),
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(Error::from(e).in_file(&dir)),
+    };
+    names.sort();
+
+    if names.is_empty() {
+        writeln!(
+            out,
+            "No profiles yet; use `alacritty-conf profile save <name>` to save the current \
+             config as one."
+        )?;
+    }
+
+    let width = names.iter().map(String::len).max().unwrap_or(0);
+    for name in &names {
+        out.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)))?;
+        write!(out, "{:<width$}", name, width = width)?;
+        out.reset()?;
+
+        match load(name) {
+            Ok(profile) => writeln!(out, "  {}", history::summary(&profile))?,
+            Err(e) => writeln!(out, "  (can't be parsed: {})", e)?,
+        }
+    }
+
+    out.flush()?;
+    Ok(())
+}
@@ -0,0 +1,298 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use std::f64::consts::PI;
+use std::fmt;
+
+/// Days to look ahead for the next transition. Even at the poles the sun
+/// rises and sets at least once a year.
+const MAX_DAYS: i64 = 370;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Period {
+    Light,
+    Dark,
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Light => "light",
+            Self::Dark => "dark",
+        })
+    }
+}
+
+/// When to use the light theme.
+#[derive(Debug, Copy, Clone)]
+pub enum Schedule {
+    /// Between two times of day. If `end` is before `start`, the light
+    /// period wraps around midnight.
+    Hours { start: NaiveTime, end: NaiveTime },
+    /// Between sunrise and sunset at the given location, in degrees north
+    /// and east.
+    Sun { latitude: f64, longitude: f64 },
+}
+
+impl Schedule {
+    /// Which theme to use at `time`.
+    pub fn period_at(&self, time: DateTime<Local>) -> Period {
+        let date = time.date().naive_local();
+
+        // every day has at least one transition, so two days back is enough
+        let mut transitions: Vec<_> = (-2..=1)
+            .flat_map(|offset| self.transitions(date + Duration::days(offset)))
+            .collect();
+        transitions.sort_by_key(|&(time, _)| time);
+
+        transitions
+            .into_iter()
+            .take_while(|&(transition, _)| transition <= time)
+            .last()
+            .map_or(Period::Dark, |(_, period)| period)
+    }
+
+    /// When the theme changes next after `time`, and to what.
+    pub fn next_transition(&self, time: DateTime<Local>) -> Option<(DateTime<Local>, Period)> {
+        let current = self.period_at(time);
+        let date = time.date().naive_local();
+
+        (-1..MAX_DAYS).find_map(|offset| {
+            let mut transitions = self.transitions(date + Duration::days(offset));
+            transitions.sort_by_key(|&(time, _)| time);
+
+            transitions
+                .into_iter()
+                .find(|&(transition, period)| transition > time && period != current)
+        })
+    }
+
+    /// The transitions on `date`. A day without any, such as during the polar
+    /// night, has a single one at noon to the period it's in.
+    fn transitions(&self, date: NaiveDate) -> Vec<(DateTime<Local>, Period)> {
+        match *self {
+            Self::Hours { start, end } => {
+                let at = |time: NaiveTime| {
+                    // a time skipped by a DST change has no transition
+                    Local.from_local_datetime(&date.and_time(time)).earliest()
+                };
+
+                let mut transitions = Vec::with_capacity(2);
+                if let Some(start) = at(start) {
+                    transitions.push((start, Period::Light));
+                }
+                // the end wins if both are at the same time
+                if let Some(end) = at(end) {
+                    transitions.push((end, Period::Dark));
+                }
+                transitions
+            }
+            Self::Sun {
+                latitude,
+                longitude,
+            } => match sun_times(date, latitude, longitude) {
+                SunTimes::Rises { sunrise, sunset } => vec![
+                    (sunrise.with_timezone(&Local), Period::Light),
+                    (sunset.with_timezone(&Local), Period::Dark),
+                ],
+                SunTimes::AlwaysUp => vec![(noon(date), Period::Light)],
+                SunTimes::AlwaysDown => vec![(noon(date), Period::Dark)],
+            },
+        }
+    }
+}
+
+fn noon(date: NaiveDate) -> DateTime<Local> {
+    Utc.from_utc_datetime(&date.and_hms(12, 0, 0))
+        .with_timezone(&Local)
+}
+
+enum SunTimes {
+    Rises {
+        sunrise: DateTime<Utc>,
+        sunset: DateTime<Utc>,
+    },
+    /// Polar day.
+    AlwaysUp,
+    /// Polar night.
+    AlwaysDown,
+}
+
+/// Julian date of the Unix epoch.
+const UNIX_EPOCH_JD: f64 = 2_440_587.5;
+/// Julian date of the J2000 epoch.
+const J2000: f64 = 2_451_545.0;
+
+/// Sunrise and sunset on `date` (UTC), using the sunrise equation. Accurate
+/// to a minute or so, which is plenty for switching themes.
+fn sun_times(date: NaiveDate, latitude: f64, longitude: f64) -> SunTimes {
+    let radians = PI / 180.0;
+
+    let midnight = date.and_hms(0, 0, 0).timestamp() as f64 / 86400.0 + UNIX_EPOCH_JD;
+    // days since J2000, to the day
+    let day = (midnight - J2000 + 0.0008).ceil();
+
+    // mean solar noon
+    let mean_noon = day - longitude / 360.0;
+    // solar mean anomaly
+    let anomaly = (357.5291 + 0.985_600_28 * mean_noon).rem_euclid(360.0) * radians;
+    // equation of the center
+    let center =
+        1.9148 * anomaly.sin() + 0.0200 * (2.0 * anomaly).sin() + 0.0003 * (3.0 * anomaly).sin();
+    // ecliptic longitude
+    let ecliptic = (anomaly / radians + center + 180.0 + 102.9372).rem_euclid(360.0) * radians;
+    let transit = J2000 + mean_noon + 0.0053 * anomaly.sin() - 0.0069 * (2.0 * ecliptic).sin();
+
+    let declination = (ecliptic.sin() * (23.4397 * radians).sin()).asin();
+    // -0.833° accounts for refraction and the size of the sun's disc
+    let hour_angle_cos = ((-0.833 * radians).sin()
+        - (latitude * radians).sin() * declination.sin())
+        / ((latitude * radians).cos() * declination.cos());
+
+    if hour_angle_cos < -1.0 {
+        return SunTimes::AlwaysUp;
+    }
+    if hour_angle_cos > 1.0 {
+        return SunTimes::AlwaysDown;
+    }
+
+    let hour_angle = hour_angle_cos.acos() / radians;
+    let from_julian = |jd: f64| {
+        let seconds = ((jd - UNIX_EPOCH_JD) * 86400.0).round() as i64;
+        Utc.timestamp(seconds, 0)
+    };
+
+    SunTimes::Rises {
+        sunrise: from_julian(transit - hour_angle / 360.0),
+        sunset: from_julian(transit + hour_angle / 360.0),
+    }
+}
+
+/// Parse a time of day such as `7:30` or `19:00`.
+pub fn parse_time(s: &str) -> Result<NaiveTime, chrono::ParseError> {
+    NaiveTime::parse_from_str(s, "%H:%M")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, Timelike};
+
+    /// Longyearbyen, Svalbard, which has both a polar day and a polar night.
+    const SVALBARD: Schedule = Schedule::Sun {
+        latitude: 78.22,
+        longitude: 15.65,
+    };
+
+    fn local(month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        // January has no DST changes to skip or repeat times
+        Local.ymd(2024, month, day).and_hms(hour, minute, 0)
+    }
+
+    fn utc(month: u32, day: u32, hour: u32) -> DateTime<Local> {
+        Utc.ymd(2024, month, day)
+            .and_hms(hour, 0, 0)
+            .with_timezone(&Local)
+    }
+
+    fn hours(start: &str, end: &str) -> Schedule {
+        Schedule::Hours {
+            start: parse_time(start).unwrap(),
+            end: parse_time(end).unwrap(),
+        }
+    }
+
+    #[test]
+    fn hours_within_a_day() {
+        let schedule = hours("7:00", "19:30");
+
+        assert_eq!(schedule.period_at(local(1, 10, 6, 59)), Period::Dark);
+        assert_eq!(schedule.period_at(local(1, 10, 7, 0)), Period::Light);
+        assert_eq!(schedule.period_at(local(1, 10, 19, 29)), Period::Light);
+        assert_eq!(schedule.period_at(local(1, 10, 19, 30)), Period::Dark);
+
+        assert_eq!(
+            schedule.next_transition(local(1, 10, 12, 0)),
+            Some((local(1, 10, 19, 30), Period::Dark))
+        );
+        assert_eq!(
+            schedule.next_transition(local(1, 10, 20, 0)),
+            Some((local(1, 11, 7, 0), Period::Light))
+        );
+    }
+
+    #[test]
+    fn hours_across_midnight() {
+        let schedule = hours("22:00", "6:00");
+
+        assert_eq!(schedule.period_at(local(1, 10, 21, 59)), Period::Dark);
+        assert_eq!(schedule.period_at(local(1, 10, 22, 0)), Period::Light);
+        assert_eq!(schedule.period_at(local(1, 11, 0, 0)), Period::Light);
+        assert_eq!(schedule.period_at(local(1, 11, 5, 59)), Period::Light);
+        assert_eq!(schedule.period_at(local(1, 11, 6, 0)), Period::Dark);
+
+        assert_eq!(
+            schedule.next_transition(local(1, 10, 23, 0)),
+            Some((local(1, 11, 6, 0), Period::Dark))
+        );
+        assert_eq!(
+            schedule.next_transition(local(1, 11, 12, 0)),
+            Some((local(1, 11, 22, 0), Period::Light))
+        );
+    }
+
+    #[test]
+    fn sunrise_and_sunset() {
+        // London on the summer solstice: sunrise 03:43 and sunset 20:21 UTC
+        let times = sun_times(NaiveDate::from_ymd(2024, 6, 20), 51.51, -0.13);
+        let (sunrise, sunset) = match times {
+            SunTimes::Rises { sunrise, sunset } => (sunrise, sunset),
+            _ => panic!("the sun rises in London"),
+        };
+
+        let minutes = |time: DateTime<Utc>| i64::from(time.hour() * 60 + time.minute());
+        assert!((minutes(sunrise) - (3 * 60 + 43)).abs() <= 2, "{}", sunrise);
+        assert!((minutes(sunset) - (20 * 60 + 21)).abs() <= 2, "{}", sunset);
+    }
+
+    #[test]
+    fn polar_day() {
+        assert!(matches!(
+            sun_times(NaiveDate::from_ymd(2024, 6, 21), 78.22, 15.65),
+            SunTimes::AlwaysUp
+        ));
+
+        for &hour in &[0, 6, 12, 18, 23] {
+            assert_eq!(SVALBARD.period_at(utc(6, 21, hour)), Period::Light);
+        }
+        // the midnight sun ends in late August
+        let (time, period) = SVALBARD.next_transition(utc(6, 21, 12)).unwrap();
+        assert_eq!((time.month(), period), (8, Period::Dark));
+    }
+
+    #[test]
+    fn polar_night() {
+        assert!(matches!(
+            sun_times(NaiveDate::from_ymd(2024, 12, 21), 78.22, 15.65),
+            SunTimes::AlwaysDown
+        ));
+
+        for &hour in &[0, 6, 12, 18, 23] {
+            assert_eq!(SVALBARD.period_at(utc(12, 21, hour)), Period::Dark);
+        }
+        // the sun comes back in mid February
+        let (time, period) = SVALBARD.next_transition(utc(12, 21, 12)).unwrap();
+        assert_eq!(
+            (time.year(), time.month(), period),
+            (2025, 2, Period::Light)
+        );
+    }
+
+    #[test]
+    fn times() {
+        assert_eq!(parse_time("7:30"), Ok(NaiveTime::from_hms(7, 30, 0)));
+        assert_eq!(parse_time("19:00"), Ok(NaiveTime::from_hms(19, 0, 0)));
+
+        for &invalid in &["", "7", "7:", "24:00", "7:60", "7:30pm", "noon"] {
+            assert!(parse_time(invalid).is_err(), "{}", invalid);
+        }
+    }
+}
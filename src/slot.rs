@@ -0,0 +1,174 @@
+use crate::color_syntax::{parse_color, ColorParseError};
+use crate::theme::{CellColor, ColorPair, IndexedColor, Theme, ThemeColors};
+use std::fmt;
+use std::str::FromStr;
+
+/// Sections of the colors that come in foreground and background pairs, in
+/// the order of `Theme::color_pairs`, with the keys of both colors.
+pub const PAIRS: [(&str, (&str, &str)); 9] = [
+    ("colors.cursor", ("text", "cursor")),
+    ("colors.vi_mode_cursor", ("text", "cursor")),
+    ("colors.selection", ("text", "background")),
+    ("colors.search.matches", ("foreground", "background")),
+    ("colors.search.focused_match", ("foreground", "background")),
+    ("colors.hints.start", ("foreground", "background")),
+    ("colors.hints.end", ("foreground", "background")),
+    ("colors.footer_bar", ("foreground", "background")),
+    ("colors.line_indicator", ("foreground", "background")),
+];
+
+/// A single color of a theme, named by its key in the config, such as
+/// `colors.normal.red`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Slot {
+    Background,
+    Foreground,
+    /// A normal color, by its position in the palette.
+    Normal(usize),
+    Bright(usize),
+    Dim(usize),
+    /// An entry of `colors.indexed_colors`, written `colors.indexed_colors.N`.
+    Indexed(u8),
+    /// The foreground (`true`) or background of an entry of `PAIRS`.
+    Pair(usize, bool),
+}
+
+impl FromStr for Slot {
+    type Err = OverrideParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || OverrideParseError::UnknownKey(s.to_owned());
+        let (section, name) = s.rsplit_once('.').ok_or_else(unknown)?;
+        let palette = || {
+            ThemeColors::NAMES
+                .iter()
+                .position(|&n| n == name)
+                .ok_or_else(unknown)
+        };
+
+        match section {
+            "colors.primary" => match name {
+                "background" => Ok(Self::Background),
+                "foreground" => Ok(Self::Foreground),
+                _ => Err(unknown()),
+            },
+            "colors.normal" => palette().map(Self::Normal),
+            "colors.bright" => palette().map(Self::Bright),
+            "colors.dim" => palette().map(Self::Dim),
+            "colors.indexed_colors" => match name.parse() {
+                Ok(index) if index >= 16 => Ok(Self::Indexed(index)),
+                _ => Err(OverrideParseError::IndexedColor(name.to_owned())),
+            },
+            _ => PAIRS
+                .iter()
+                .position(|&(key, _)| key == section)
+                .and_then(|i| {
+                    let (foreground, background) = PAIRS[i].1;
+                    match name {
+                        n if n == foreground => Some(Self::Pair(i, true)),
+                        n if n == background => Some(Self::Pair(i, false)),
+                        _ => None,
+                    }
+                })
+                .ok_or_else(unknown),
+        }
+    }
+}
+
+/// A color of a theme set on the command line, e.g.
+/// `colors.normal.red=#ff5555`.
+#[derive(Debug, Copy, Clone)]
+pub struct Override {
+    pub slot: Slot,
+    pub color: CellColor,
+}
+
+impl Override {
+    /// Set the color in `theme`. Dim colors the theme doesn't have are
+    /// derived from the normal colors first.
+    pub fn apply(&self, theme: &mut Theme) {
+        let rgb = match (self.slot, self.color) {
+            (Slot::Pair(i, foreground), color) => {
+                let pairs = theme.color_pairs_mut();
+                let pair = pairs[i].get_or_insert_with(ColorPair::default);
+                if foreground {
+                    pair.foreground = Some(color);
+                } else {
+                    pair.background = Some(color);
+                }
+                return;
+            }
+            (_, CellColor::Rgb(rgb)) => rgb,
+            _ => unreachable!("only pairs can refer to the colors of a cell"),
+        };
+
+        match self.slot {
+            Slot::Background => theme.background = rgb,
+            Slot::Foreground => theme.foreground = rgb,
+            Slot::Normal(i) => *theme.normal.named_mut()[i].1 = rgb,
+            Slot::Bright(i) => *theme.bright.named_mut()[i].1 = rgb,
+            Slot::Dim(i) => {
+                let normal = theme.normal;
+                let dim = theme.dim.get_or_insert_with(|| normal.dimmed());
+                *dim.named_mut()[i].1 = rgb;
+            }
+            Slot::Indexed(index) => {
+                match theme.indexed_colors.iter_mut().find(|c| c.index == index) {
+                    Some(indexed) => indexed.color = rgb,
+                    None => theme.indexed_colors.extend(IndexedColor::new(index, rgb)),
+                }
+            }
+            Slot::Pair(..) => unreachable!(),
+        }
+    }
+}
+
+impl FromStr for Override {
+    type Err = OverrideParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = s
+            .split_once('=')
+            .ok_or_else(|| OverrideParseError::MissingColor(s.to_owned()))?;
+        let slot: Slot = key.trim().parse()?;
+
+        let color = match (slot, value.trim()) {
+            (Slot::Pair(..), "CellForeground") => CellColor::CellForeground,
+            (Slot::Pair(..), "CellBackground") => CellColor::CellBackground,
+            (_, value) => parse_color(value)
+                .map(CellColor::Rgb)
+                .map_err(OverrideParseError::Color)?,
+        };
+
+        Ok(Self { slot, color })
+    }
+}
+
+#[derive(Debug)]
+pub enum OverrideParseError {
+    MissingColor(String),
+    UnknownKey(String),
+    IndexedColor(String),
+    Color(ColorParseError),
+}
+
+impl fmt::Display for OverrideParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingColor(ref s) => {
+                write!(
+                    f,
+                    "expected KEY=COLOR, e.g. colors.normal.red=#ff5555, not '{}'",
+                    s
+                )
+            }
+            Self::UnknownKey(ref key) => write!(f, "unknown color key '{}'", key),
+            Self::IndexedColor(ref index) => write!(
+                f,
+                "invalid index '{}' of colors.indexed_colors; must be between 16 and 255",
+                index
+            ),
+            Self::Color(ref e) => write!(f, "{}", e),
+        }
+    }
+}
@@ -1,9 +1,10 @@
-use rgb::RGB8;
+use crate::transform::{Invert, InvertMode, Transform};
+use rgb::{ComponentMap, RGB8};
 use std::fmt;
-use std::num::ParseIntError;
+use std::num::{ParseFloatError, ParseIntError};
 use std::str::FromStr;
 
-#[derive(Debug, Default, Copy, Clone)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
 pub struct ThemeColors {
     pub black: RGB8,
     pub red: RGB8,
@@ -15,20 +16,65 @@ pub struct ThemeColors {
     pub white: RGB8,
 }
 
-impl Invert for ThemeColors {
-    fn invert(&mut self) {
-        self.black.invert();
-        self.red.invert();
-        self.green.invert();
-        self.yellow.invert();
-        self.blue.invert();
-        self.magenta.invert();
-        self.cyan.invert();
-        self.white.invert();
+impl ThemeColors {
+    /// Factor alacritty scales the normal colors by to derive dim colors.
+    pub const DIM_FACTOR: f32 = 0.66;
+
+    /// Names of the colors, in the order of the palette.
+    pub const NAMES: [&'static str; 8] = [
+        "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+    ];
+
+    /// The colors in the order of the palette, along with their names.
+    pub fn named(&self) -> [(&'static str, RGB8); 8] {
+        let [black, red, green, yellow, blue, magenta, cyan, white] = Self::NAMES;
+
+        [
+            (black, self.black),
+            (red, self.red),
+            (green, self.green),
+            (yellow, self.yellow),
+            (blue, self.blue),
+            (magenta, self.magenta),
+            (cyan, self.cyan),
+            (white, self.white),
+        ]
+    }
+
+    /// Mutable references to the colors, in the order of the palette.
+    pub fn named_mut(&mut self) -> [(&'static str, &mut RGB8); 8] {
+        let [black, red, green, yellow, blue, magenta, cyan, white] = Self::NAMES;
+
+        [
+            (black, &mut self.black),
+            (red, &mut self.red),
+            (green, &mut self.green),
+            (yellow, &mut self.yellow),
+            (blue, &mut self.blue),
+            (magenta, &mut self.magenta),
+            (cyan, &mut self.cyan),
+            (white, &mut self.white),
+        ]
+    }
+
+    /// Derive dim colors the way alacritty does when none are configured.
+    pub fn dimmed(&self) -> Self {
+        let dim = |c: RGB8| c.map(|x| (f32::from(x) * Self::DIM_FACTOR) as u8);
+
+        Self {
+            black: dim(self.black),
+            red: dim(self.red),
+            green: dim(self.green),
+            yellow: dim(self.yellow),
+            blue: dim(self.blue),
+            magenta: dim(self.magenta),
+            cyan: dim(self.cyan),
+            white: dim(self.white),
+        }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Theme {
     /// Background color.
     pub background: RGB8,
@@ -41,6 +87,132 @@ pub struct Theme {
 
     /// Bright colors.
     pub bright: ThemeColors,
+
+    /// Dim colors. When unset, alacritty derives them from the normal colors.
+    pub dim: Option<ThemeColors>,
+
+    /// Colors for indices 16 to 255 of the 256 color palette.
+    pub indexed_colors: Vec<IndexedColor>,
+
+    /// Cursor colors. `foreground` is the text under the cursor.
+    pub cursor: Option<ColorPair>,
+
+    /// Cursor colors in vi mode. `foreground` is the text under the cursor.
+    pub vi_mode_cursor: Option<ColorPair>,
+
+    /// Selection colors. `foreground` is the selected text.
+    pub selection: Option<ColorPair>,
+
+    /// Colors of search matches.
+    pub search_matches: Option<ColorPair>,
+
+    /// Colors of the currently focused search match.
+    pub search_focused_match: Option<ColorPair>,
+
+    /// Colors of the first character of keyboard hints.
+    pub hints_start: Option<ColorPair>,
+
+    /// Colors of the remaining characters of keyboard hints.
+    pub hints_end: Option<ColorPair>,
+
+    /// Colors of the search bar and message bar.
+    pub footer_bar: Option<ColorPair>,
+
+    /// Colors of the scrollback position indicator.
+    pub line_indicator: Option<ColorPair>,
+}
+
+impl Theme {
+    /// Replace every color of the theme with `f` of it. References to the
+    /// colors of a cell are kept.
+    pub fn map_colors<F: FnMut(RGB8) -> RGB8>(&mut self, mut f: F) {
+        let map_palette = |palette: &mut ThemeColors, f: &mut F| {
+            for (_, color) in palette.named_mut().iter_mut() {
+                **color = f(**color);
+            }
+        };
+
+        self.background = f(self.background);
+        self.foreground = f(self.foreground);
+        map_palette(&mut self.normal, &mut f);
+        map_palette(&mut self.bright, &mut f);
+        if let Some(ref mut dim) = self.dim {
+            map_palette(dim, &mut f);
+        }
+        for indexed in &mut self.indexed_colors {
+            indexed.color = f(indexed.color);
+        }
+
+        for pair in self
+            .color_pairs_mut()
+            .iter_mut()
+            .filter_map(|pair| pair.as_mut())
+        {
+            for cell in [&mut pair.foreground, &mut pair.background].iter_mut() {
+                if let Some(CellColor::Rgb(rgb)) = cell {
+                    *rgb = f(*rgb);
+                }
+            }
+        }
+    }
+
+    /// The colors that come in foreground and background pairs, such as the
+    /// cursor colors, in the order of the config.
+    pub const fn color_pairs(&self) -> [Option<ColorPair>; 9] {
+        [
+            self.cursor,
+            self.vi_mode_cursor,
+            self.selection,
+            self.search_matches,
+            self.search_focused_match,
+            self.hints_start,
+            self.hints_end,
+            self.footer_bar,
+            self.line_indicator,
+        ]
+    }
+
+    /// `color_pairs`, mutably.
+    pub fn color_pairs_mut(&mut self) -> [&mut Option<ColorPair>; 9] {
+        [
+            &mut self.cursor,
+            &mut self.vi_mode_cursor,
+            &mut self.selection,
+            &mut self.search_matches,
+            &mut self.search_focused_match,
+            &mut self.hints_start,
+            &mut self.hints_end,
+            &mut self.footer_bar,
+            &mut self.line_indicator,
+        ]
+    }
+
+    /// Find the preset this theme was created from, going by its primary,
+    /// normal and bright colors. The flag is set if the preset was inverted.
+    pub fn preset(&self) -> Option<(ColorTheme, bool)> {
+        let same_colors = |theme: &Self| {
+            theme.background == self.background
+                && theme.foreground == self.foreground
+                && theme.normal == self.normal
+                && theme.bright == self.bright
+        };
+
+        ColorTheme::ALL.iter().find_map(|&preset| {
+            let theme = Self::from(preset);
+            if same_colors(&theme) {
+                return Some((preset, false));
+            }
+
+            [InvertMode::Perceptual, InvertMode::Rgb]
+                .iter()
+                .any(|&mode| {
+                    let mut inverted = theme.clone();
+                    Invert(mode).apply(&mut inverted);
+                    same_colors(&inverted)
+                })
+                .then_some((preset, true))
+        })
+    }
 }
 
 impl Default for Theme {
@@ -50,28 +222,56 @@ impl Default for Theme {
     }
 }
 
-impl Invert for Theme {
-    fn invert(&mut self) {
-        self.background.invert();
-        self.foreground.invert();
-        self.normal.invert();
-        self.bright.invert();
+/// An entry of `colors.indexed_colors`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct IndexedColor {
+    /// Index into the 256 color palette, between 16 and 255.
+    pub index: u8,
+    pub color: RGB8,
+}
+
+impl IndexedColor {
+    pub fn new(index: u8, color: RGB8) -> Option<Self> {
+        if index >= 16 {
+            Some(Self { index, color })
+        } else {
+            None
+        }
     }
 }
 
-pub trait Invert {
-    fn invert(&mut self);
+/// A color that is either fixed, or taken from the cell it is drawn over.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CellColor {
+    Rgb(RGB8),
+    CellForeground,
+    CellBackground,
 }
 
-impl Invert for RGB8 {
-    fn invert(&mut self) {
-        self.r = 255 - self.r;
-        self.g = 255 - self.g;
-        self.b = 255 - self.b;
+impl From<RGB8> for CellColor {
+    fn from(rgb: RGB8) -> Self {
+        Self::Rgb(rgb)
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+/// Foreground and background colors of a UI element. Unset colors are left
+/// to alacritty's defaults.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct ColorPair {
+    pub foreground: Option<CellColor>,
+    pub background: Option<CellColor>,
+}
+
+impl ColorPair {
+    pub fn new<F: Into<CellColor>, B: Into<CellColor>>(foreground: F, background: B) -> Self {
+        Self {
+            foreground: Some(foreground.into()),
+            background: Some(background.into()),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ColorTheme {
     Afterglow,
     Argonaut,
@@ -87,25 +287,32 @@ pub enum ColorTheme {
 }
 
 // TODO field 'family' should just be called 'name' or something
-#[derive(Debug)]
+#[derive(Debug, Default, Clone)]
 pub struct Font {
     /// Font family
     pub family: Option<String>,
-    pub size: FontSize,
+    pub size: Option<FontSize>,
 }
 
-impl Default for Font {
-    fn default() -> Self {
-        Self {
-            family: None,
-            size: FontSize::default(),
+/// Font size in points. Alacritty accepts fractional sizes (e.g. `11.5`).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FontSize(pub f32);
+
+impl FontSize {
+    /// Smallest font size that will be written to the config.
+    pub const MIN: f32 = 1.0;
+    /// Largest font size that will be written to the config.
+    pub const MAX: f32 = 300.0;
+
+    pub fn new(size: f32) -> Result<Self, FontSizeParseError> {
+        if size.is_finite() && (Self::MIN..=Self::MAX).contains(&size) {
+            Ok(Self(size))
+        } else {
+            Err(FontSizeParseError::OutOfRange(size))
         }
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-pub struct FontSize(pub u16);
-
 impl fmt::Display for FontSize {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0)
@@ -114,7 +321,7 @@ impl fmt::Display for FontSize {
 
 impl Default for FontSize {
     fn default() -> Self {
-        Self(20)
+        Self(20.0)
     }
 }
 
@@ -144,6 +351,20 @@ impl From<ColorTheme> for Theme {
                     cyan: RGB8::new(134, 211, 206),
                     white: RGB8::new(247, 247, 247),
                 },
+                dim: None,
+                indexed_colors: Vec::new(),
+                cursor: Some(ColorPair::new(
+                    RGB8::new(44, 44, 44),
+                    RGB8::new(217, 217, 217),
+                )),
+                vi_mode_cursor: None,
+                selection: None,
+                search_matches: None,
+                search_focused_match: None,
+                hints_start: None,
+                hints_end: None,
+                footer_bar: None,
+                line_indicator: None,
             },
             ColorTheme::Argonaut => Self {
                 background: RGB8::new(41, 44, 62),
@@ -168,6 +389,20 @@ impl From<ColorTheme> for Theme {
                     cyan: RGB8::new(115, 251, 241),
                     white: RGB8::new(254, 254, 248),
                 },
+                dim: None,
+                indexed_colors: Vec::new(),
+                cursor: Some(ColorPair::new(
+                    RGB8::new(255, 38, 30),
+                    RGB8::new(255, 38, 30),
+                )),
+                vi_mode_cursor: None,
+                selection: None,
+                search_matches: None,
+                search_focused_match: None,
+                hints_start: None,
+                hints_end: None,
+                footer_bar: None,
+                line_indicator: None,
             },
             ColorTheme::AyuDark => Self {
                 background: RGB8::new(10, 14, 20),
@@ -192,6 +427,17 @@ impl From<ColorTheme> for Theme {
                     cyan: RGB8::new(149, 230, 203),
                     white: RGB8::new(255, 255, 255),
                 },
+                dim: None,
+                indexed_colors: Vec::new(),
+                cursor: None,
+                vi_mode_cursor: None,
+                selection: None,
+                search_matches: None,
+                search_focused_match: None,
+                hints_start: None,
+                hints_end: None,
+                footer_bar: None,
+                line_indicator: None,
             },
             ColorTheme::Gruvbox => Self {
                 background: RGB8::new(40, 40, 40),
@@ -216,6 +462,17 @@ impl From<ColorTheme> for Theme {
                     cyan: RGB8::new(142, 192, 124),
                     white: RGB8::new(235, 219, 178),
                 },
+                dim: None,
+                indexed_colors: Vec::new(),
+                cursor: None,
+                vi_mode_cursor: None,
+                selection: None,
+                search_matches: None,
+                search_focused_match: None,
+                hints_start: None,
+                hints_end: None,
+                footer_bar: None,
+                line_indicator: None,
             },
             ColorTheme::Material => Self {
                 background: RGB8::new(38, 50, 56),
@@ -240,6 +497,17 @@ impl From<ColorTheme> for Theme {
                     cyan: RGB8::new(137, 221, 255),
                     white: RGB8::new(255, 255, 255),
                 },
+                dim: None,
+                indexed_colors: Vec::new(),
+                cursor: None,
+                vi_mode_cursor: None,
+                selection: None,
+                search_matches: None,
+                search_focused_match: None,
+                hints_start: None,
+                hints_end: None,
+                footer_bar: None,
+                line_indicator: None,
             },
             ColorTheme::MonokaiSoda => Self {
                 background: RGB8::new(26, 26, 26),
@@ -264,6 +532,17 @@ impl From<ColorTheme> for Theme {
                     cyan: RGB8::new(88, 209, 235),
                     white: RGB8::new(246, 246, 239),
                 },
+                dim: None,
+                indexed_colors: Vec::new(),
+                cursor: None,
+                vi_mode_cursor: None,
+                selection: None,
+                search_matches: None,
+                search_focused_match: None,
+                hints_start: None,
+                hints_end: None,
+                footer_bar: None,
+                line_indicator: None,
             },
             ColorTheme::AyuMirage => Self {
                 background: RGB8::new(32, 39, 52),
@@ -288,6 +567,17 @@ impl From<ColorTheme> for Theme {
                     cyan: RGB8::new(149, 230, 203),
                     white: RGB8::new(255, 255, 255),
                 },
+                dim: None,
+                indexed_colors: Vec::new(),
+                cursor: None,
+                vi_mode_cursor: None,
+                selection: None,
+                search_matches: None,
+                search_focused_match: None,
+                hints_start: None,
+                hints_end: None,
+                footer_bar: None,
+                line_indicator: None,
             },
             ColorTheme::Base16DefaultDark => Self {
                 background: RGB8::new(24, 24, 24),
@@ -312,6 +602,45 @@ impl From<ColorTheme> for Theme {
                     cyan: RGB8::new(134, 193, 185),
                     white: RGB8::new(248, 248, 248),
                 },
+                dim: None,
+                indexed_colors: vec![
+                    IndexedColor {
+                        index: 16,
+                        color: RGB8::new(220, 150, 86),
+                    },
+                    IndexedColor {
+                        index: 17,
+                        color: RGB8::new(161, 105, 70),
+                    },
+                    IndexedColor {
+                        index: 18,
+                        color: RGB8::new(40, 40, 40),
+                    },
+                    IndexedColor {
+                        index: 19,
+                        color: RGB8::new(56, 56, 56),
+                    },
+                    IndexedColor {
+                        index: 20,
+                        color: RGB8::new(184, 184, 184),
+                    },
+                    IndexedColor {
+                        index: 21,
+                        color: RGB8::new(232, 232, 232),
+                    },
+                ],
+                cursor: Some(ColorPair::new(
+                    RGB8::new(24, 24, 24),
+                    RGB8::new(216, 216, 216),
+                )),
+                vi_mode_cursor: None,
+                selection: None,
+                search_matches: None,
+                search_focused_match: None,
+                hints_start: None,
+                hints_end: None,
+                footer_bar: None,
+                line_indicator: None,
             },
             ColorTheme::Breeze => Self {
                 background: RGB8::new(35, 38, 39),
@@ -336,6 +665,26 @@ impl From<ColorTheme> for Theme {
                     cyan: RGB8::new(22, 160, 133),
                     white: RGB8::new(255, 255, 255),
                 },
+                dim: Some(ThemeColors {
+                    black: RGB8::new(49, 54, 59),
+                    red: RGB8::new(120, 50, 40),
+                    green: RGB8::new(23, 162, 98),
+                    yellow: RGB8::new(182, 86, 25),
+                    blue: RGB8::new(27, 102, 143),
+                    magenta: RGB8::new(97, 74, 115),
+                    cyan: RGB8::new(24, 108, 96),
+                    white: RGB8::new(99, 104, 109),
+                }),
+                indexed_colors: Vec::new(),
+                cursor: None,
+                vi_mode_cursor: None,
+                selection: None,
+                search_matches: None,
+                search_focused_match: None,
+                hints_start: None,
+                hints_end: None,
+                footer_bar: None,
+                line_indicator: None,
             },
             ColorTheme::Dracula => Self {
                 background: RGB8::new(40, 42, 54),
@@ -360,6 +709,41 @@ impl From<ColorTheme> for Theme {
                     cyan: RGB8::new(154, 237, 254),
                     white: RGB8::new(230, 230, 230),
                 },
+                dim: None,
+                indexed_colors: Vec::new(),
+                cursor: Some(ColorPair::new(
+                    CellColor::CellBackground,
+                    CellColor::CellForeground,
+                )),
+                vi_mode_cursor: Some(ColorPair::new(
+                    CellColor::CellBackground,
+                    CellColor::CellForeground,
+                )),
+                selection: Some(ColorPair::new(
+                    CellColor::CellForeground,
+                    RGB8::new(68, 71, 90),
+                )),
+                search_matches: Some(ColorPair::new(
+                    RGB8::new(68, 71, 90),
+                    RGB8::new(80, 250, 123),
+                )),
+                search_focused_match: Some(ColorPair::new(
+                    RGB8::new(68, 71, 90),
+                    RGB8::new(255, 184, 108),
+                )),
+                hints_start: Some(ColorPair::new(
+                    RGB8::new(40, 42, 54),
+                    RGB8::new(241, 250, 140),
+                )),
+                hints_end: Some(ColorPair::new(
+                    RGB8::new(241, 250, 140),
+                    RGB8::new(40, 42, 54),
+                )),
+                footer_bar: Some(ColorPair::new(
+                    RGB8::new(40, 42, 54),
+                    RGB8::new(248, 248, 242),
+                )),
+                line_indicator: None,
             },
             ColorTheme::Kitty => Self {
                 background: RGB8::new(0, 0, 0),
@@ -384,6 +768,17 @@ impl From<ColorTheme> for Theme {
                     cyan: RGB8::new(20, 255, 255),
                     white: RGB8::new(255, 255, 255),
                 },
+                dim: None,
+                indexed_colors: Vec::new(),
+                cursor: None,
+                vi_mode_cursor: None,
+                selection: None,
+                search_matches: None,
+                search_focused_match: None,
+                hints_start: None,
+                hints_end: None,
+                footer_bar: None,
+                line_indicator: None,
             },
         }
     }
@@ -402,108 +797,367 @@ impl fmt::Display for ColorThemeParseError {
     }
 }
 
+impl ColorTheme {
+    /// Every preset, in the order they're listed by `--list-themes`.
+    pub const ALL: [Self; 11] = [
+        Self::Afterglow,
+        Self::Argonaut,
+        Self::AyuDark,
+        Self::AyuMirage,
+        Self::Base16DefaultDark,
+        Self::Breeze,
+        Self::Dracula,
+        Self::Kitty,
+        Self::Gruvbox,
+        Self::Material,
+        Self::MonokaiSoda,
+    ];
+
+    /// Name of the preset on the command line.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Afterglow => "afterglow",
+            Self::Argonaut => "argonaut",
+            Self::AyuDark => "ayu-dark",
+            Self::AyuMirage => "ayu-mirage",
+            Self::Base16DefaultDark => "base16",
+            Self::Breeze => "breeze",
+            Self::Dracula => "dracula",
+            Self::Kitty => "kitty",
+            Self::Gruvbox => "gruvbox",
+            Self::Material => "material",
+            Self::MonokaiSoda => "monokai-soda",
+        }
+    }
+}
+
+impl fmt::Display for ColorTheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
 impl FromStr for ColorTheme {
     type Err = ColorThemeParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_ascii_lowercase().as_str() {
-            "afterglow" => Ok(Self::Afterglow),
-            "argonaut" => Ok(Self::Argonaut),
-            "ayu-dark" => Ok(Self::AyuDark),
-            "ayu-mirage" => Ok(Self::AyuMirage),
-            "base16" => Ok(Self::Base16DefaultDark),
-            "breeze" => Ok(Self::Breeze),
-            "dracula" => Ok(Self::Dracula),
-            "kitty" => Ok(Self::Kitty),
-            "gruvbox" => Ok(Self::Gruvbox),
-            "material" => Ok(Self::Material),
-            "monokai-soda" => Ok(Self::MonokaiSoda),
-            _ => Err(ColorThemeParseError::UnknownTheme(s.to_owned())),
-        }
+        let name = s.to_ascii_lowercase();
+
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|theme| theme.name() == name)
+            .ok_or_else(|| ColorThemeParseError::UnknownTheme(s.to_owned()))
     }
 }
 
 impl Font {
-    pub fn new<S: Into<String>>(family: Option<S>, size: u16) -> Self {
+    pub fn new<S: Into<String>>(family: Option<S>, size: Option<FontSize>) -> Self {
         Self {
-            family: match family {
-                Some(f) => Some(f.into()),
-                None => None,
-            },
-            size: FontSize(size),
+            family: family.map(Into::into),
+            size,
         }
     }
 }
 
-impl fmt::Display for Font {
+/// Font size as given on the command line. A leading `+` or `-` makes the
+/// value relative to the size in the current config.
+#[derive(Debug, Copy, Clone)]
+pub enum FontSizeArg {
+    Absolute(FontSize),
+    Relative(f32),
+}
+
+impl FontSizeArg {
+    /// Resolve the argument against the font size of the current config.
+    pub fn apply(self, current: FontSize) -> Result<FontSize, FontSizeParseError> {
+        match self {
+            Self::Absolute(size) => Ok(size),
+            Self::Relative(delta) => FontSize::new(current.0 + delta),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum FontSizeParseError {
+    FloatParse(ParseFloatError),
+    OutOfRange(f32),
+}
+
+impl fmt::Display for FontSizeParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "\
-font:{}
-    size: {}",
-            {
-                match &self.family {
-                    Some(f) => format!(
-                        "
-    normal:
-        family: {}",
-                        f
-                    ),
-                    None => String::new(),
-                }
-            },
-            self.size
-        )
+        match self {
+            Self::FloatParse(ref e) => write!(f, "font size failed to parse: {}", e),
+            Self::OutOfRange(size) => write!(
+                f,
+                "font size {} is out of range; must be between {} and {}",
+                size,
+                FontSize::MIN,
+                FontSize::MAX
+            ),
+        }
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-pub struct Window {
-    width: u16,
-    height: u16,
+impl FromStr for FontSizeArg {
+    type Err = FontSizeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let size = s.parse::<f32>().map_err(Self::Err::FloatParse)?;
+
+        if s.starts_with('+') || s.starts_with('-') {
+            if size.is_finite() {
+                Ok(Self::Relative(size))
+            } else {
+                Err(Self::Err::OutOfRange(size))
+            }
+        } else {
+            FontSize::new(size).map(Self::Absolute)
+        }
+    }
 }
 
-impl Default for Window {
-    fn default() -> Self {
-        Self::new(80, 25)
+impl fmt::Display for Font {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("font:")?;
+        if let Some(ref family) = self.family {
+            write!(f, "\n    normal:\n        family: {}", yaml_quote(family))?;
+        }
+        if let Some(size) = self.size {
+            write!(f, "\n    size: {}", size)?;
+        }
+        Ok(())
     }
 }
 
+/// The `window` section of the config. Fields that are not set are left out
+/// when writing.
+#[derive(Debug, Default, Clone)]
+pub struct Window {
+    pub dimensions: Option<Dimensions>,
+    pub position: Option<Position>,
+    pub padding: Option<Padding>,
+    pub dynamic_padding: Option<bool>,
+    pub decorations: Option<Decorations>,
+    pub opacity: Option<Opacity>,
+    pub startup_mode: Option<StartupMode>,
+    pub title: Option<String>,
+    pub dynamic_title: Option<bool>,
+    pub class: Option<Class>,
+}
+
 impl Window {
-    pub const fn new(width: u16, height: u16) -> Self {
-        Self { width, height }
+    /// Whether no field is set.
+    pub fn is_empty(&self) -> bool {
+        self.dimensions.is_none()
+            && self.position.is_none()
+            && self.padding.is_none()
+            && self.dynamic_padding.is_none()
+            && self.decorations.is_none()
+            && self.opacity.is_none()
+            && self.startup_mode.is_none()
+            && self.title.is_none()
+            && self.dynamic_title.is_none()
+            && self.class.is_none()
     }
 
-    pub fn new_or_default(width: Option<u16>, height: Option<u16>) -> Self {
-        match width {
-            Some(w) => match height {
-                Some(h) => Self::new(w, h),
-                None => Self::default(),
-            },
-            None => Self::default(),
+    /// Fill every field not set in `self` from `old`.
+    pub fn merge(self, old: Self) -> Self {
+        Self {
+            dimensions: self.dimensions.or(old.dimensions),
+            position: self.position.or(old.position),
+            padding: self.padding.or(old.padding),
+            dynamic_padding: self.dynamic_padding.or(old.dynamic_padding),
+            decorations: self.decorations.or(old.decorations),
+            opacity: self.opacity.or(old.opacity),
+            startup_mode: self.startup_mode.or(old.startup_mode),
+            title: self.title.or(old.title),
+            dynamic_title: self.dynamic_title.or(old.dynamic_title),
+            class: self.class.or(old.class),
         }
     }
 }
 
+/// Quote a string for YAML, escaping single quotes.
+pub fn yaml_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
 impl fmt::Display for Window {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "\
-window:
+        f.write_str("window:")?;
+
+        if let Some(dimensions) = self.dimensions {
+            write!(
+                f,
+                "
     dimensions:
         columns: {}
         lines: {}",
-            self.width, self.height
-        )
+                dimensions.columns, dimensions.lines
+            )?;
+        }
+        if let Some(position) = self.position {
+            write!(
+                f,
+                "
+    position:
+        x: {}
+        y: {}",
+                position.x, position.y
+            )?;
+        }
+        if let Some(padding) = self.padding {
+            write!(
+                f,
+                "
+    padding:
+        x: {}
+        y: {}",
+                padding.x, padding.y
+            )?;
+        }
+        if let Some(dynamic_padding) = self.dynamic_padding {
+            write!(f, "\n    dynamic_padding: {}", dynamic_padding)?;
+        }
+        if let Some(decorations) = self.decorations {
+            write!(f, "\n    decorations: {}", decorations)?;
+        }
+        if let Some(opacity) = self.opacity {
+            write!(f, "\n    opacity: {}", opacity)?;
+        }
+        if let Some(startup_mode) = self.startup_mode {
+            write!(f, "\n    startup_mode: {}", startup_mode)?;
+        }
+        if let Some(ref title) = self.title {
+            write!(f, "\n    title: {}", yaml_quote(title))?;
+        }
+        if let Some(dynamic_title) = self.dynamic_title {
+            write!(f, "\n    dynamic_title: {}", dynamic_title)?;
+        }
+        if let Some(ref class) = self.class {
+            write!(
+                f,
+                "
+    class:
+        instance: {}
+        general: {}",
+                yaml_quote(&class.instance),
+                yaml_quote(&class.general)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Window size in terminal cells.
+#[derive(Debug, Copy, Clone)]
+pub struct Dimensions {
+    pub columns: u16,
+    pub lines: u16,
+}
+
+impl Default for Dimensions {
+    fn default() -> Self {
+        Self::new(80, 25)
+    }
+}
+
+impl Dimensions {
+    pub const fn new(columns: u16, lines: u16) -> Self {
+        Self { columns, lines }
+    }
+}
+
+/// Window position in pixels, relative to the top-left corner of the screen.
+#[derive(Debug, Copy, Clone)]
+pub struct Position {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Blank space added around the terminal, in pixels.
+#[derive(Debug, Copy, Clone)]
+pub struct Padding {
+    pub x: u16,
+    pub y: u16,
+}
+
+/// Window decorations (title bar and borders).
+#[derive(Debug, Copy, Clone)]
+pub enum Decorations {
+    Full,
+    None,
+    Transparent,
+    Buttonless,
+}
+
+/// Background opacity, between 0.0 and 1.0.
+#[derive(Debug, Copy, Clone)]
+pub struct Opacity(f32);
+
+impl Opacity {
+    pub fn new(opacity: f32) -> Result<Self, WindowParseError> {
+        if (0.0..=1.0).contains(&opacity) {
+            Ok(Self(opacity))
+        } else {
+            Err(WindowParseError::OpacityOutOfRange(opacity))
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum StartupMode {
+    Windowed,
+    Maximized,
+    Fullscreen,
+    SimpleFullscreen,
+}
+
+/// Window class, used by X11 window managers.
+#[derive(Debug, Clone)]
+pub struct Class {
+    pub instance: String,
+    pub general: String,
+}
+
+impl fmt::Display for Decorations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Full => "full",
+            Self::None => "none",
+            Self::Transparent => "transparent",
+            Self::Buttonless => "buttonless",
+        })
+    }
+}
+
+impl fmt::Display for Opacity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl fmt::Display for StartupMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Windowed => "Windowed",
+            Self::Maximized => "Maximized",
+            Self::Fullscreen => "Fullscreen",
+            Self::SimpleFullscreen => "SimpleFullscreen",
+        })
     }
 }
 
 #[derive(Debug)]
 pub enum WindowSizeParseError {
-    // TODO correct tihs error message, this isn't what necessarily happens
+    /// There's no `x` between the integers.
+    MissingX,
     TooManyXs,
+    /// Index (0 | 1) of the integer that's missing.
+    MissingInt(u8),
     IntParse(
         // index (0 | 1), which integer failed to parse
         u8,
@@ -515,9 +1169,15 @@ pub enum WindowSizeParseError {
 impl fmt::Display for WindowSizeParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            Self::MissingX => f.write_str("expected two integers separated by 'x', e.g. 80x24"),
             Self::TooManyXs => {
                 f.write_str("too many dimensions; only one 'x' delimiter is allowed")
             }
+            Self::MissingInt(idx) => write!(
+                f,
+                "{} integer is missing",
+                if *idx == 0 { "first" } else { "second" }
+            ),
             Self::IntParse(ref idx, ref e) => write!(
                 f,
                 "{} integer failed to parse: {}",
@@ -528,22 +1188,235 @@ impl fmt::Display for WindowSizeParseError {
     }
 }
 
-impl FromStr for Window {
+/// Parse a pair of integers in the form `AxB`.
+fn parse_pair<T: FromStr<Err = ParseIntError>>(s: &str) -> Result<(T, T), WindowSizeParseError> {
+    let (a, b) = s.split_once('x').ok_or(WindowSizeParseError::MissingX)?;
+    if b.contains('x') {
+        return Err(WindowSizeParseError::TooManyXs);
+    }
+
+    let parse = |idx, s: &str| match s {
+        "" => Err(WindowSizeParseError::MissingInt(idx)),
+        s => s
+            .parse::<T>()
+            .map_err(|e| WindowSizeParseError::IntParse(idx, e)),
+    };
+    Ok((parse(0, a)?, parse(1, b)?))
+}
+
+impl FromStr for Dimensions {
     type Err = WindowSizeParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let args: Vec<&str> = s.split_terminator('x').collect();
+        parse_pair(s).map(|(columns, lines)| Self::new(columns, lines))
+    }
+}
 
-        if args.len() == 2 {
-            match args[0].parse::<u16>() {
-                Ok(width) => match args[1].parse::<u16>() {
-                    Ok(height) => Ok(Self::new(width, height)),
-                    Err(e) => Err(Self::Err::IntParse(1, e)),
-                },
-                Err(e) => Err(Self::Err::IntParse(0, e)),
-            }
-        } else {
-            Err(Self::Err::TooManyXs)
+impl FromStr for Position {
+    type Err = WindowSizeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_pair(s).map(|(x, y)| Self { x, y })
+    }
+}
+
+impl FromStr for Padding {
+    type Err = WindowSizeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_pair(s).map(|(x, y)| Self { x, y })
+    }
+}
+
+#[derive(Debug)]
+pub enum WindowParseError {
+    OpacityFloatParse(ParseFloatError),
+    OpacityOutOfRange(f32),
+    UnknownDecorations(String),
+    UnknownStartupMode(String),
+}
+
+impl fmt::Display for WindowParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OpacityFloatParse(ref e) => write!(f, "opacity failed to parse: {}", e),
+            Self::OpacityOutOfRange(opacity) => write!(
+                f,
+                "opacity {} is out of range; must be between 0 and 1",
+                opacity
+            ),
+            Self::UnknownDecorations(ref d) => write!(
+                f,
+                "Unknown decorations: {} (expected full, none, transparent or buttonless)",
+                d
+            ),
+            Self::UnknownStartupMode(ref m) => write!(
+                f,
+                "Unknown startup mode: {} (expected windowed, maximized, fullscreen or \
+                 simple-fullscreen)",
+                m
+            ),
+        }
+    }
+}
+
+impl FromStr for Opacity {
+    type Err = WindowParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s.parse().map_err(Self::Err::OpacityFloatParse)?)
+    }
+}
+
+impl FromStr for Decorations {
+    type Err = WindowParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "full" => Ok(Self::Full),
+            "none" => Ok(Self::None),
+            "transparent" => Ok(Self::Transparent),
+            "buttonless" => Ok(Self::Buttonless),
+            _ => Err(Self::Err::UnknownDecorations(s.to_owned())),
+        }
+    }
+}
+
+impl FromStr for StartupMode {
+    type Err = WindowParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().replace(&['-', '_'][..], "").as_str() {
+            "windowed" => Ok(Self::Windowed),
+            "maximized" => Ok(Self::Maximized),
+            "fullscreen" => Ok(Self::Fullscreen),
+            "simplefullscreen" => Ok(Self::SimpleFullscreen),
+            _ => Err(Self::Err::UnknownStartupMode(s.to_owned())),
         }
     }
 }
+
+impl FromStr for Class {
+    type Err = std::convert::Infallible;
+
+    /// Parse `<instance>` or `<instance>,<general>`, like alacritty's
+    /// `--class`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.split_once(',') {
+            Some((instance, general)) => Self {
+                instance: instance.to_owned(),
+                general: general.to_owned(),
+            },
+            None => Self {
+                instance: s.to_owned(),
+                general: s.to_owned(),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn font_size_args() {
+        assert!(
+            matches!("11.5".parse(), Ok(FontSizeArg::Absolute(FontSize(size))) if size == 11.5)
+        );
+        assert!(matches!("+2".parse(), Ok(FontSizeArg::Relative(size)) if size == 2.0));
+        assert!(matches!(" -1.5 ".parse(), Ok(FontSizeArg::Relative(size)) if size == -1.5));
+        assert!(matches!(
+            "big".parse::<FontSizeArg>(),
+            Err(FontSizeParseError::FloatParse(_))
+        ));
+        assert!(matches!(
+            "+inf".parse::<FontSizeArg>(),
+            Err(FontSizeParseError::OutOfRange(_))
+        ));
+    }
+
+    #[test]
+    fn font_size_bounds() {
+        assert!(FontSize::new(FontSize::MIN).is_ok());
+        assert!(FontSize::new(FontSize::MAX).is_ok());
+        assert!(matches!(
+            "0.5".parse::<FontSizeArg>(),
+            Err(FontSizeParseError::OutOfRange(size)) if size == 0.5
+        ));
+        assert!(matches!(
+            "300.5".parse::<FontSizeArg>(),
+            Err(FontSizeParseError::OutOfRange(_))
+        ));
+        assert!(f32::NAN.to_string().parse::<FontSizeArg>().is_err());
+    }
+
+    #[test]
+    fn pairs() {
+        let pair = |s: &str| parse_pair::<i32>(s);
+
+        assert!(matches!(pair("80x24"), Ok((80, 24))));
+        assert!(matches!(pair("-10x0"), Ok((-10, 0))));
+        assert!(matches!(pair("80"), Err(WindowSizeParseError::MissingX)));
+        assert!(matches!(
+            pair("x24"),
+            Err(WindowSizeParseError::MissingInt(0))
+        ));
+        assert!(matches!(
+            pair("80x"),
+            Err(WindowSizeParseError::MissingInt(1))
+        ));
+        assert!(matches!(
+            pair("x"),
+            Err(WindowSizeParseError::MissingInt(0))
+        ));
+        assert!(matches!(
+            pair("80x24x2"),
+            Err(WindowSizeParseError::TooManyXs)
+        ));
+        assert!(matches!(
+            pair("ax24"),
+            Err(WindowSizeParseError::IntParse(0, _))
+        ));
+        assert!(matches!(
+            pair("80x2.5"),
+            Err(WindowSizeParseError::IntParse(1, _))
+        ));
+        // dimensions can't be negative
+        assert!(matches!(
+            "-80x24".parse::<Dimensions>(),
+            Err(WindowSizeParseError::IntParse(0, _))
+        ));
+    }
+
+    #[test]
+    fn font_families_are_quoted() {
+        let font = Font::new(Some("*Fira Code: Retina #2"), Some(FontSize(11.5)));
+        assert_eq!(
+            font.to_string(),
+            "font:\n    normal:\n        family: '*Fira Code: Retina #2'\n    size: 11.5"
+        );
+    }
+
+    #[test]
+    fn relative_font_sizes() {
+        let apply = |arg: &str, current: f32| {
+            arg.parse::<FontSizeArg>()
+                .unwrap()
+                .apply(FontSize(current))
+                .map(|size| size.0)
+        };
+
+        assert_eq!(apply("+2", 11.0).unwrap(), 13.0);
+        assert_eq!(apply("-1.5", 11.0).unwrap(), 9.5);
+        assert_eq!(apply("14", 11.0).unwrap(), 14.0);
+        assert!(matches!(
+            apply("-11", 11.0),
+            Err(FontSizeParseError::OutOfRange(size)) if size == 0.0
+        ));
+        assert!(matches!(
+            apply("+1", 300.0),
+            Err(FontSizeParseError::OutOfRange(_))
+        ));
+    }
+}
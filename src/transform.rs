@@ -0,0 +1,237 @@
+use crate::color::{from_linear, to_linear, Oklch};
+use crate::theme::{Theme, ThemeColors};
+use rgb::RGB8;
+use std::fmt;
+use std::num::ParseFloatError;
+use std::str::FromStr;
+
+/// A change made to every color of a theme.
+pub trait Transform: fmt::Debug {
+    /// The color `rgb` becomes.
+    fn color(&self, rgb: RGB8) -> RGB8;
+
+    /// Change every color of `theme`.
+    fn apply(&self, theme: &mut Theme) {
+        theme.map_colors(|rgb| self.color(rgb));
+    }
+}
+
+/// Invert the colors, turning a dark theme into a light one.
+#[derive(Debug, Copy, Clone)]
+pub struct Invert(pub InvertMode);
+
+impl Transform for Invert {
+    fn color(&self, rgb: RGB8) -> RGB8 {
+        match self.0 {
+            InvertMode::Perceptual => {
+                let mut lch = Oklch::from(rgb);
+                lch.l = 1.0 - lch.l;
+                lch.to_rgb8()
+            }
+            InvertMode::Rgb => RGB8::new(255 - rgb.r, 255 - rgb.g, 255 - rgb.b),
+        }
+    }
+
+    fn apply(&self, theme: &mut Theme) {
+        theme.map_colors(|rgb| self.color(rgb));
+
+        // black is the dark gray and white the light one in light themes as
+        // well, so after flipping their lightness they trade places
+        if self.0 == InvertMode::Perceptual {
+            let swap = |palette: &mut ThemeColors| {
+                std::mem::swap(&mut palette.black, &mut palette.white);
+            };
+            swap(&mut theme.normal);
+            swap(&mut theme.bright);
+            if let Some(ref mut dim) = theme.dim {
+                swap(dim);
+            }
+        }
+    }
+}
+
+/// How colors are inverted.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum InvertMode {
+    /// Flip the perceived lightness, keeping hue and chroma, so that red
+    /// stays red.
+    Perceptual,
+    /// Negate every RGB channel, which also turns every hue into its
+    /// complement.
+    Rgb,
+}
+
+impl FromStr for InvertMode {
+    type Err = InvertModeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "perceptual" => Ok(Self::Perceptual),
+            "rgb" => Ok(Self::Rgb),
+            _ => Err(InvertModeParseError(s.to_owned())),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct InvertModeParseError(String);
+
+impl fmt::Display for InvertModeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown invert mode '{}'; expected perceptual or rgb",
+            self.0
+        )
+    }
+}
+
+/// Rotate the hue by a number of degrees, in OKLCH.
+#[derive(Debug, Copy, Clone)]
+pub struct Hue(pub f64);
+
+impl Transform for Hue {
+    fn color(&self, rgb: RGB8) -> RGB8 {
+        let mut lch = Oklch::from(rgb);
+        lch.h = (lch.h + self.0).rem_euclid(360.0);
+        lch.to_rgb8()
+    }
+}
+
+impl FromStr for Hue {
+    type Err = TransformParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let degrees = s
+            .trim_end_matches('°')
+            .parse()
+            .map_err(TransformParseError::Float)?;
+        finite(degrees).map(Self)
+    }
+}
+
+/// Scale the chroma by a percentage, e.g. -20% for duller colors. -100%
+/// turns every color gray.
+#[derive(Debug, Copy, Clone)]
+pub struct Saturate(pub f64);
+
+impl Transform for Saturate {
+    fn color(&self, rgb: RGB8) -> RGB8 {
+        let mut lch = Oklch::from(rgb);
+        lch.c *= 1.0 + self.0 / 100.0;
+        lch.to_rgb8()
+    }
+}
+
+impl FromStr for Saturate {
+    type Err = TransformParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let percent = parse_percent(s)?;
+        if percent < -100.0 {
+            return Err(TransformParseError::BelowMinusHundred(percent));
+        }
+        Ok(Self(percent))
+    }
+}
+
+/// Raise the lightness by a percentage of the range from black to white, in
+/// OKLCH. Negative values darken.
+#[derive(Debug, Copy, Clone)]
+pub struct Lighten(pub f64);
+
+impl Transform for Lighten {
+    fn color(&self, rgb: RGB8) -> RGB8 {
+        let mut lch = Oklch::from(rgb);
+        lch.l += self.0 / 100.0;
+        lch.to_rgb8()
+    }
+}
+
+impl FromStr for Lighten {
+    type Err = TransformParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_percent(s).map(Self)
+    }
+}
+
+/// Make the colors warmer (more orange) or, with a negative value, cooler
+/// (more blue), like the white balance of a photo. 100 is about as warm as
+/// the night modes of screens go.
+#[derive(Debug, Copy, Clone)]
+pub struct Warmth(pub f64);
+
+impl Warmth {
+    /// Gain of the red channel at a warmth of 100. Blue loses as much, and
+    /// green gains a third of it.
+    const MAX_GAIN: f64 = 0.3;
+}
+
+impl Transform for Warmth {
+    fn color(&self, rgb: RGB8) -> RGB8 {
+        let gain = self.0 / 100.0 * Self::MAX_GAIN;
+
+        RGB8::new(
+            from_linear(to_linear(rgb.r) * (1.0 + gain)),
+            from_linear(to_linear(rgb.g) * (1.0 + gain / 3.0)),
+            from_linear(to_linear(rgb.b) * (1.0 - gain)),
+        )
+    }
+}
+
+impl FromStr for Warmth {
+    type Err = TransformParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let warmth = s.parse().map_err(TransformParseError::Float)?;
+        let warmth = finite(warmth)?;
+        if !(-100.0..=100.0).contains(&warmth) {
+            return Err(TransformParseError::WarmthOutOfRange(warmth));
+        }
+        Ok(Self(warmth))
+    }
+}
+
+/// Parse a percentage such as `-20%`. The sign is optional.
+fn parse_percent(s: &str) -> Result<f64, TransformParseError> {
+    let percent = s
+        .strip_suffix('%')
+        .unwrap_or(s)
+        .parse()
+        .map_err(TransformParseError::Float)?;
+    finite(percent)
+}
+
+fn finite(value: f64) -> Result<f64, TransformParseError> {
+    if value.is_finite() {
+        Ok(value)
+    } else {
+        Err(TransformParseError::NotFinite)
+    }
+}
+
+#[derive(Debug)]
+pub enum TransformParseError {
+    Float(ParseFloatError),
+    NotFinite,
+    BelowMinusHundred(f64),
+    WarmthOutOfRange(f64),
+}
+
+impl fmt::Display for TransformParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Float(ref e) => write!(f, "{}", e),
+            Self::NotFinite => f.write_str("must be a finite number"),
+            Self::BelowMinusHundred(percent) => {
+                write!(f, "{}% is below -100%, which is already gray", percent)
+            }
+            Self::WarmthOutOfRange(warmth) => write!(
+                f,
+                "warmth {} is out of range; must be between -100 and 100",
+                warmth
+            ),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::error::{Error, Result};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+/// How long a file has to be left alone after a change before it's read.
+/// Editors often save in several steps, e.g. truncating and then writing.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Waits for a file to be saved.
+pub struct Watcher {
+    path: PathBuf,
+    /// File name of `path`, to filter the events of its directory by.
+    name: OsString,
+    #[cfg(target_os = "linux")]
+    inotify: inotify::Inotify,
+    #[cfg(not(target_os = "linux"))]
+    modified: Option<std::time::SystemTime>,
+}
+
+impl Watcher {
+    #[cfg(target_os = "linux")]
+    pub fn new(path: &Path) -> Result<Self> {
+        use inotify::{Inotify, WatchMask};
+
+        let (dir, name) = split(path);
+        let in_dir = |e| Error::from(e).in_file(&dir);
+
+        // watch the directory rather than the file, since many editors save
+        // by replacing the file with a new one
+        let mut inotify = Inotify::init().map_err(in_dir)?;
+        inotify
+            .add_watch(
+                &dir,
+                WatchMask::CLOSE_WRITE | WatchMask::MOVED_TO | WatchMask::CREATE,
+            )
+            .map_err(in_dir)?;
+
+        Ok(Self {
+            path: path.to_owned(),
+            name,
+            inotify,
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn new(path: &Path) -> Result<Self> {
+        let (_, name) = split(path);
+
+        Ok(Self {
+            path: path.to_owned(),
+            name,
+            modified: modified(path),
+        })
+    }
+
+    /// Block until the file is saved, and then until it's been left alone for
+    /// a moment.
+    pub fn wait(&mut self) -> Result<()> {
+        let mut buffer = [0; 4096];
+
+        while !self.changed(&mut buffer, true)? {}
+        loop {
+            thread::sleep(DEBOUNCE);
+            if !self.changed(&mut buffer, false)? {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Whether the file changed since the last call, waiting for a change if
+    /// `block` is set.
+    #[cfg(target_os = "linux")]
+    fn changed(&mut self, buffer: &mut [u8], block: bool) -> Result<bool> {
+        let events = if block {
+            self.inotify.read_events_blocking(buffer)
+        } else {
+            self.inotify.read_events(buffer)
+        };
+        let name = &self.name;
+
+        Ok(events
+            .map_err(|e| Error::from(e).in_file(&self.path))?
+            .any(|event| event.name == Some(name.as_os_str())))
+    }
+
+    /// Without inotify, the modification time is polled instead.
+    #[cfg(not(target_os = "linux"))]
+    fn changed(&mut self, _buffer: &mut [u8], block: bool) -> Result<bool> {
+        loop {
+            let modified = modified(&self.path);
+            if modified != self.modified {
+                self.modified = modified;
+                return Ok(true);
+            }
+            if !block {
+                return Ok(false);
+            }
+            thread::sleep(DEBOUNCE);
+        }
+    }
+}
+
+/// Split `path` into its directory and file name.
+fn split(path: &Path) -> (PathBuf, OsString) {
+    let dir = match path.parent() {
+        Some(dir) if dir != Path::new("") => dir.to_owned(),
+        _ => PathBuf::from("."),
+    };
+    let name = path.file_name().unwrap_or_default().to_owned();
+
+    (dir, name)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn modified(path: &Path) -> Option<std::time::SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}